@@ -1,17 +1,286 @@
-use std::env;
+use std::{
+    env, fs,
+    io::{self, Read},
+    process,
+};
 
-use ema::{repl::repl, run_code};
+use ema::{
+    check_source,
+    error::Error,
+    expression::Expression,
+    formatter::{format_source, DEFAULT_WIDTH},
+    parser::parse,
+    repl::repl,
+    run_source,
+    testing::run_tests,
+    transform::expand,
+};
+
+const USAGE: &str = "\
+usage: ema [command] [options] [file] [args...]
+
+commands:
+  run      run a program, the default when no command is given
+  repl     start an interactive session, the default without arguments
+  check    parse files without running them
+  fmt      format files in place, - formats stdin to stdout
+  test     run the deftest tests of *_test.ema files under the given paths
+  ast      print the syntax tree of a program
+
+options:
+  -e, --eval <code>    use code as the program (run, ast)
+  -I, --path <dir>     add a directory searched by import (run, test)
+      --expanded       print the tree after syntax transformations (ast)
+      --check          list files which are not formatted instead of changing them (fmt)
+      --width <n>      maximum line width, 80 by default (fmt)
+  -h, --help           print this help
+  -V, --version        print the version
+
+A file named - reads the program from stdin. Arguments after the program are passed to it as argv.";
+
+const COMMANDS: &[&str] = &["run", "repl", "check", "fmt", "test", "ast"];
+
+#[derive(Default)]
+struct Options {
+    search_paths: Vec<String>,
+    inline: Option<String>, // program given by -e
+    expanded: bool,
+    check: bool,
+    width: Option<usize>,
+    help: bool,
+    version: bool,
+    inputs: Vec<String>, // files or directories
+    argv: Vec<String>,
+}
 
 fn main() {
-    let cwd = env::current_dir().unwrap();
-    let args: Vec<String> = std::env::args().collect();
+    let mut args = env::args().skip(1).peekable();
+    let command = match args.peek() {
+        None => "repl".to_string(),
+        Some(arg) if COMMANDS.contains(&arg.as_str()) => args.next().unwrap(),
+        Some(_) => "run".to_string(),
+    };
+
+    let status = match parse_options(&command, args) {
+        Ok(options) if options.help => {
+            println!("{}", USAGE);
+            0
+        }
+        Ok(options) if options.version => {
+            println!("ema {}", env!("CARGO_PKG_VERSION"));
+            0
+        }
+        Ok(options) => match command.as_str() {
+            "repl" => {
+                repl();
+                0
+            }
+            "check" => check(options),
+            "fmt" => fmt(options),
+            "test" => {
+                let mut paths = options.inputs;
+                if paths.is_empty() {
+                    paths.push(".".to_string());
+                }
+                run_tests(paths, options.search_paths)
+            }
+            "ast" => ast(options),
+            _ => run(options),
+        },
+        Err(msg) => {
+            eprintln!("{}\n\n{}", msg, USAGE);
+            2
+        }
+    };
+
+    process::exit(status);
+}
+
+fn parse_options(command: &str, mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options::default();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("missing value after {}", arg))
+        };
+
+        match arg.as_str() {
+            "-h" | "--help" => options.help = true,
+            "-V" | "--version" => options.version = true,
+            "-I" | "--path" => options.search_paths.push(value()?),
+            "-e" | "--eval" if matches!(command, "run" | "ast") => options.inline = Some(value()?),
+            "--expanded" if command == "ast" => options.expanded = true,
+            "--check" if command == "fmt" => options.check = true,
+            "--width" if command == "fmt" => {
+                let width = value()?;
+                match width.parse::<usize>() {
+                    Ok(width) if width > 0 => options.width = Some(width),
+                    _ => return Err(format!("invalid width {}", width)),
+                }
+            }
+            opt if opt.starts_with('-') && opt != "-" => {
+                return Err(format!("unknown option {} for ema {}", opt, command))
+            }
+            _ if command == "run" => {
+                // everything after the program belongs to it
+                match options.inline {
+                    Some(_) => options.argv.push(arg),
+                    None => options.inputs.push(arg),
+                }
+                options.argv.extend(args);
+                break;
+            }
+            _ if command == "repl" => return Err("ema repl takes no files".to_string()),
+            _ => options.inputs.push(arg),
+        }
+    }
+
+    if command == "ast" && options.inputs.len() + options.inline.iter().count() > 1 {
+        return Err("ema ast takes a single program".to_string());
+    }
+
+    Ok(options)
+}
+
+// program text and the directory its imports are resolved from
+fn read_program(options: &Options) -> Result<(String, String), Error> {
+    let cwd = env::current_dir()?.display().to_string();
+    match (&options.inline, options.inputs.first()) {
+        (Some(code), _) => Ok((code.clone(), cwd)),
+        (None, Some(file)) if file == "-" => {
+            let mut source = String::new();
+            io::stdin().read_to_string(&mut source)?;
+            Ok((source, cwd))
+        }
+        (None, Some(file)) => {
+            let path = env::current_dir()?.join(file);
+            let source = fs::read_to_string(&path)
+                .map_err(|err| Error::Io(format!("cannot read {}: {}", file, err)))?;
+            let dir = path.parent().map(|dir| dir.display().to_string());
+            Ok((source, dir.unwrap_or(cwd)))
+        }
+        (None, None) => Err(Error::Invalid(
+            "missing program, give a file, - or -e <code>".to_string(),
+        )),
+    }
+}
 
-    if args.len() == 1 {
-        repl();
-    } else {
-        let file_name = &args[1];
-        let file_path = format!("{}/{}", cwd.display(), file_name);
+fn run(options: Options) -> i32 {
+    match read_program(&options) {
+        Ok((source, cwf_path)) => run_source(
+            &source,
+            cwf_path,
+            options.search_paths,
+            options.argv,
+            options.inline.is_some(),
+        ),
+        Err(err) => {
+            eprintln!("{}", err);
+            1
+        }
+    }
+}
+
+fn check(options: Options) -> i32 {
+    if options.inputs.is_empty() {
+        eprintln!("ema check expects files to check");
+        return 2;
+    }
+
+    let mut failed = false;
+    for file in &options.inputs {
+        let program = Options {
+            inputs: vec![file.clone()],
+            ..Options::default()
+        };
+        match read_program(&program).and_then(|(source, _)| check_source(&source)) {
+            Ok(()) => println!("{}: ok", file),
+            Err(err) => {
+                failed = true;
+                eprintln!("{}: {}", file, err);
+            }
+        }
+    }
+
+    failed as i32
+}
+
+// rewrites files in place, or with --check lists the files which would change
+fn fmt(options: Options) -> i32 {
+    if options.inputs.is_empty() {
+        eprintln!("ema fmt expects files to format");
+        return 2;
+    }
+
+    let width = options.width.unwrap_or(DEFAULT_WIDTH);
+    let mut status = 0;
+    for file in &options.inputs {
+        let program = Options {
+            inputs: vec![file.clone()],
+            ..Options::default()
+        };
+        let res = read_program(&program).and_then(|(source, _)| {
+            let formatted = format_source(&source, width)?;
+            match (file.as_str(), options.check) {
+                (_, true) if formatted != source => {
+                    println!("{}", file);
+                    status = 1;
+                }
+                (_, true) => (),
+                ("-", false) => print!("{}", formatted),
+                (_, false) if formatted != source => fs::write(file, formatted)?,
+                (_, false) => (),
+            }
+            Ok(())
+        });
+
+        if let Err(err) = res {
+            eprintln!("{}: {}", file, err);
+            status = 1;
+        }
+    }
+
+    status
+}
+
+fn ast(options: Options) -> i32 {
+    let forms = read_program(&options).and_then(|(source, _)| match parse(&source)? {
+        Expression::List(forms) if options.expanded => forms.iter().map(expand).collect(),
+        Expression::List(forms) => Ok(forms),
+        other => Ok(vec![other]),
+    });
+
+    match forms {
+        Ok(forms) => {
+            let mut out = String::new();
+            for form in &forms {
+                dump(form, 0, &mut out);
+            }
+            print!("{}", out);
+            0
+        }
+        Err(err) => {
+            eprintln!("{}", err);
+            1
+        }
+    }
+}
 
-        run_code(file_path);
+// one node per line, children indented under their list
+fn dump(exp: &Expression, depth: usize, out: &mut String) {
+    let indent = "  ".repeat(depth);
+    match exp {
+        Expression::List(items) => {
+            *out += &format!("{}List\n", indent);
+            for item in items {
+                dump(item, depth + 1, out);
+            }
+        }
+        Expression::String(str) => *out += &format!("{}String {:?}\n", indent, str),
+        Expression::Symbol(name) => *out += &format!("{}Symbol {}\n", indent, name),
+        Expression::Number(num) => *out += &format!("{}Number {}\n", indent, num),
+        Expression::Boolean(bool) => *out += &format!("{}Boolean {}\n", indent, bool),
+        Expression::Native(name) => *out += &format!("{}Native {}\n", indent, name),
+        other => *out += &format!("{}{}\n", indent, other),
     }
 }
@@ -0,0 +1,230 @@
+use crate::{error::Error, eval::Evaluator, expression::Expression};
+
+use super::{string, NativeFn};
+
+pub const FUNCTIONS: &[(&str, NativeFn)] = &[("format", format), ("repr", repr)];
+
+// (format template args...) replaces {} placeholders, see format_template
+fn format(eval: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    let Some((template, args)) = args.split_first() else {
+        return Err(Error::Invalid(
+            "format expects a template string".to_string(),
+        ));
+    };
+
+    Ok(Expression::String(format_template(
+        eval,
+        string("format", template)?,
+        args,
+    )?))
+}
+
+fn repr(eval: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    let [value] = args.as_slice() else {
+        return Err(Error::Invalid(format!(
+            "repr expects 1 argument(s), got {}",
+            args.len()
+        )));
+    };
+
+    Ok(Expression::String(repr_of(eval, value)?))
+}
+
+// {} takes the next argument, {2} a given one and {:spec} or {2:spec} formats it with
+// [[fill]align][+][0][width][.precision][?], {{ and }} are literal braces
+pub fn format_template(
+    eval: &mut Evaluator,
+    template: &str,
+    args: &[Expression],
+) -> Result<String, Error> {
+    let mut out = String::new();
+    let mut next_arg = 0;
+    let mut chars = template.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                out.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                out.push('}');
+            }
+            '}' => {
+                return Err(Error::Invalid(
+                    "unmatched '}' in format template".to_string(),
+                ))
+            }
+            '{' => {
+                let mut placeholder = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(ch) => placeholder.push(ch),
+                        None => {
+                            return Err(Error::Invalid(
+                                "unclosed '{' in format template".to_string(),
+                            ))
+                        }
+                    }
+                }
+
+                let (position, spec) = placeholder
+                    .split_once(':')
+                    .unwrap_or((placeholder.as_str(), ""));
+                let idx = match position {
+                    "" => {
+                        next_arg += 1;
+                        next_arg - 1
+                    }
+                    position => position.parse::<usize>().map_err(|_| {
+                        Error::Invalid(format!("invalid format placeholder {{{}}}", placeholder))
+                    })?,
+                };
+                let value = args.get(idx).ok_or(Error::Invalid(format!(
+                    "format placeholder {} has no argument, {} given",
+                    idx,
+                    args.len()
+                )))?;
+
+                out += &apply_spec(eval, value, &Spec::parse(spec)?)?;
+            }
+            ch => out.push(ch),
+        }
+    }
+
+    Ok(out)
+}
+
+#[derive(Default)]
+struct Spec {
+    fill: Option<char>,
+    align: Option<char>,
+    sign: bool,
+    zero: bool,
+    width: usize,
+    precision: Option<usize>,
+    repr: bool,
+}
+
+impl Spec {
+    fn parse(spec: &str) -> Result<Spec, Error> {
+        let invalid = || Error::Invalid(format!("invalid format spec {}", spec));
+        let chars = spec.chars().collect::<Vec<char>>();
+        let mut res = Spec::default();
+        let mut idx = 0;
+
+        if chars.len() > 1 && matches!(chars[1], '<' | '>' | '^') {
+            res.fill = Some(chars[0]);
+            res.align = Some(chars[1]);
+            idx = 2;
+        } else if matches!(chars.first(), Some('<' | '>' | '^')) {
+            res.align = Some(chars[0]);
+            idx = 1;
+        }
+
+        if chars.get(idx) == Some(&'+') {
+            res.sign = true;
+            idx += 1;
+        }
+        if chars.get(idx) == Some(&'0') {
+            res.zero = true;
+            idx += 1;
+        }
+
+        let digits = |idx: &mut usize| {
+            let start = *idx;
+            while chars.get(*idx).is_some_and(|ch| ch.is_ascii_digit()) {
+                *idx += 1;
+            }
+            chars[start..*idx].iter().collect::<String>()
+        };
+
+        let width = digits(&mut idx);
+        if !width.is_empty() {
+            res.width = width.parse().map_err(|_| invalid())?;
+        }
+
+        if chars.get(idx) == Some(&'.') {
+            idx += 1;
+            let precision = digits(&mut idx);
+            res.precision = Some(precision.parse().map_err(|_| invalid())?);
+        }
+
+        match &chars[idx..] {
+            [] => (),
+            ['?'] => res.repr = true,
+            _ => return Err(invalid()),
+        }
+
+        Ok(res)
+    }
+}
+
+fn apply_spec(eval: &mut Evaluator, value: &Expression, spec: &Spec) -> Result<String, Error> {
+    let text = match (value, spec.precision) {
+        _ if spec.repr => repr_of(eval, value)?,
+        (Expression::Number(num), Some(precision)) => format!("{:.*}", precision, num),
+        (Expression::Number(num), None) => num.to_string(),
+        (_, Some(precision)) => eval.stringify(value)?.chars().take(precision).collect(),
+        (_, None) => eval.stringify(value)?,
+    };
+
+    let is_number = matches!(value, Expression::Number(_)) && !spec.repr;
+    let text = match is_number && spec.sign && !text.starts_with('-') {
+        true => format!("+{}", text),
+        false => text,
+    };
+
+    let len = text.chars().count();
+    if len >= spec.width {
+        return Ok(text);
+    }
+    let padding = spec.width - len;
+
+    // zero padding goes between the sign and the digits
+    if is_number && spec.zero && spec.align.is_none() {
+        let (sign, digits) = match text.starts_with(['+', '-']) {
+            true => text.split_at(1),
+            false => ("", text.as_str()),
+        };
+        return Ok(format!("{}{}{}", sign, "0".repeat(padding), digits));
+    }
+
+    let fill = spec.fill.unwrap_or(' ').to_string();
+    let align = spec.align.unwrap_or(if is_number { '>' } else { '<' });
+    Ok(match align {
+        '>' => format!("{}{}", fill.repeat(padding), text),
+        '^' => format!(
+            "{}{}{}",
+            fill.repeat(padding / 2),
+            text,
+            fill.repeat(padding - padding / 2)
+        ),
+        _ => format!("{}{}", text, fill.repeat(padding)),
+    })
+}
+
+// like stringify but strings are quoted, so "1" and 1 print differently
+pub fn repr_of(eval: &mut Evaluator, value: &Expression) -> Result<String, Error> {
+    match value {
+        Expression::String(str) => Ok(format!("{:?}", str)),
+        Expression::List(items) => Ok(format!(
+            "({})",
+            items
+                .iter()
+                .map(|item| repr_of(eval, item))
+                .collect::<Result<Vec<String>, Error>>()?
+                .join(" ")
+        )),
+        Expression::Map(map) => Ok(format!(
+            "{{{}}}",
+            map.iter()
+                .map(|(key, value)| Ok(format!("{:?}: {}", key, repr_of(eval, value)?)))
+                .collect::<Result<Vec<String>, Error>>()?
+                .join(", ")
+        )),
+        other => eval.stringify(other),
+    }
+}
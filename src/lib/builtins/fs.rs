@@ -0,0 +1,158 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, BufRead, BufReader, Write},
+    path::Path,
+};
+
+use crate::{error::Error, eval::Evaluator, expression::Expression};
+
+use super::{expect_args, string, truthy, NativeFn};
+
+pub const FUNCTIONS: &[(&str, NativeFn)] = &[
+    ("read", read),
+    ("read-lines", read_lines),
+    ("each-line", each_line),
+    ("write", write),
+    ("append", append),
+    ("exists?", exists),
+    ("file?", is_file),
+    ("dir?", is_dir),
+    ("list-dir", list_dir),
+    ("mkdir", mkdir),
+    ("rmdir", rmdir),
+    ("remove", remove),
+];
+
+// io errors keep the path and the failed action, e.g. cannot read a.txt: No such file...
+fn io_error(action: &str, path: &str, err: io::Error) -> Error {
+    Error::Io(format!("cannot {} {}: {}", action, path, err))
+}
+
+fn read(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("read", &args, 1)?;
+    let path = string("read", &args[0])?;
+    let ctx = fs::read_to_string(path).map_err(|err| io_error("read", path, err))?;
+    Ok(Expression::String(ctx))
+}
+
+fn read_lines(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("read-lines", &args, 1)?;
+    let path = string("read-lines", &args[0])?;
+    let ctx = fs::read_to_string(path).map_err(|err| io_error("read", path, err))?;
+    Ok(Expression::List(
+        ctx.lines()
+            .map(|line| Expression::String(line.to_string()))
+            .collect(),
+    ))
+}
+
+// (each-line path func) reads the file lazily, one line per call
+fn each_line(eval: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("each-line", &args, 2)?;
+    let path = string("each-line", &args[0])?;
+    let file = fs::File::open(path).map_err(|err| io_error("read", path, err))?;
+
+    let mut count = 0;
+    for line in BufReader::new(file).lines() {
+        let line = line.map_err(|err| io_error("read", path, err))?;
+        eval.call_function(&args[1], vec![Expression::String(line)])?;
+        count += 1;
+    }
+
+    Ok(Expression::Number(count as f64))
+}
+
+fn write(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("write", &args, 2)?;
+    let (path, ctx) = (string("write", &args[0])?, string("write", &args[1])?);
+    fs::write(path, ctx).map_err(|err| io_error("write", path, err))?;
+    Ok(Expression::Void)
+}
+
+fn append(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("append", &args, 2)?;
+    let (path, ctx) = (string("append", &args[0])?, string("append", &args[1])?);
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(ctx.as_bytes()))
+        .map_err(|err| io_error("append to", path, err))?;
+    Ok(Expression::Void)
+}
+
+fn exists(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("exists?", &args, 1)?;
+    Ok(Expression::Boolean(
+        Path::new(string("exists?", &args[0])?).exists(),
+    ))
+}
+
+fn is_file(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("file?", &args, 1)?;
+    Ok(Expression::Boolean(
+        Path::new(string("file?", &args[0])?).is_file(),
+    ))
+}
+
+fn is_dir(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("dir?", &args, 1)?;
+    Ok(Expression::Boolean(
+        Path::new(string("dir?", &args[0])?).is_dir(),
+    ))
+}
+
+// entry names sorted, without the directory prefix
+fn list_dir(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("list-dir", &args, 1)?;
+    let path = string("list-dir", &args[0])?;
+
+    let mut names = fs::read_dir(path)
+        .and_then(|entries| {
+            entries
+                .map(|entry| entry.map(|entry| entry.file_name().to_string_lossy().to_string()))
+                .collect::<Result<Vec<String>, io::Error>>()
+        })
+        .map_err(|err| io_error("list", path, err))?;
+    names.sort();
+
+    Ok(Expression::List(
+        names.into_iter().map(Expression::String).collect(),
+    ))
+}
+
+// creates missing parent directories as well
+fn mkdir(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("mkdir", &args, 1)?;
+    let path = string("mkdir", &args[0])?;
+    fs::create_dir_all(path).map_err(|err| io_error("create directory", path, err))?;
+    Ok(Expression::Void)
+}
+
+// (rmdir path) removes an empty directory, (rmdir path true) removes its content too
+fn rmdir(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    let (path, recursive) = match args.as_slice() {
+        [path] => (string("rmdir", path)?, false),
+        [path, recursive] => (string("rmdir", path)?, truthy(recursive)),
+        _ => {
+            return Err(Error::Invalid(format!(
+                "rmdir expects 1 or 2 argument(s), got {}",
+                args.len()
+            )))
+        }
+    };
+
+    match recursive {
+        true => fs::remove_dir_all(path),
+        false => fs::remove_dir(path),
+    }
+    .map_err(|err| io_error("remove directory", path, err))?;
+    Ok(Expression::Void)
+}
+
+fn remove(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("remove", &args, 1)?;
+    let path = string("remove", &args[0])?;
+    fs::remove_file(path).map_err(|err| io_error("remove", path, err))?;
+    Ok(Expression::Void)
+}
@@ -0,0 +1,101 @@
+use std::{
+    fmt::Debug,
+    io::{self, BufRead, BufReader, Read, Write},
+};
+
+use crate::{error::Error, eval::Evaluator, expression::Expression};
+
+use super::{expect_args, string, NativeFn};
+
+pub const FUNCTIONS: &[(&str, NativeFn)] = &[
+    ("read-line", read_line),
+    ("read-all", read_all),
+    ("each-input-line", each_input_line),
+    ("input", input),
+];
+
+// where read-line and friends read from, stdin unless an embedder sets another source
+pub struct Input(Box<dyn BufRead>);
+
+impl Input {
+    pub fn new(source: impl BufRead + 'static) -> Self {
+        Input(Box::new(source))
+    }
+
+    // next line without its line ending, none at the end of the input
+    fn next_line(&mut self) -> Result<Option<String>, Error> {
+        let mut line = String::new();
+        if self.0.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        if line.ends_with('\n') {
+            line.pop();
+            if line.ends_with('\r') {
+                line.pop();
+            }
+        }
+        Ok(Some(line))
+    }
+}
+
+impl Default for Input {
+    fn default() -> Self {
+        Input::new(BufReader::new(io::stdin()))
+    }
+}
+
+impl Debug for Input {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Input")
+    }
+}
+
+// nil once the input is exhausted
+fn read_line(eval: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("read-line", &args, 0)?;
+    Ok(eval
+        .input()
+        .next_line()?
+        .map(Expression::String)
+        .unwrap_or(Expression::Void))
+}
+
+fn read_all(eval: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("read-all", &args, 0)?;
+    let mut ctx = String::new();
+    eval.input().0.read_to_string(&mut ctx)?;
+    Ok(Expression::String(ctx))
+}
+
+// (each-input-line func) calls func as each line arrives and returns the number of lines
+fn each_input_line(eval: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("each-input-line", &args, 1)?;
+    let mut count = 0;
+    while let Some(line) = eval.input().next_line()? {
+        eval.call_function(&args[0], vec![Expression::String(line)])?;
+        count += 1;
+    }
+
+    Ok(Expression::Number(count as f64))
+}
+
+// (input) or (input prompt), the prompt is written to stdout before reading a line
+fn input(eval: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    match args.as_slice() {
+        [] => (),
+        [prompt] => {
+            let mut stdout = io::stdout();
+            write!(stdout, "{}", string("input", prompt)?)?;
+            stdout.flush()?;
+        }
+        _ => {
+            return Err(Error::Invalid(format!(
+                "input expects 0 or 1 argument(s), got {}",
+                args.len()
+            )))
+        }
+    }
+
+    read_line(eval, vec![])
+}
@@ -0,0 +1,34 @@
+use std::io::Write;
+
+use crate::{error::Error, eval::Evaluator, expression::Expression};
+
+use super::NativeFn;
+
+pub const FUNCTIONS: &[(&str, NativeFn)] =
+    &[("write", write), ("writeln", writeln), ("eprint", eprint)];
+
+// arguments are printed separated by spaces, strings without quotes
+fn join(eval: &mut Evaluator, args: &[Expression]) -> Result<String, Error> {
+    Ok(args
+        .iter()
+        .map(|arg| eval.stringify(arg))
+        .collect::<Result<Vec<String>, Error>>()?
+        .join(" "))
+}
+
+fn write(eval: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    let mut stdout = std::io::stdout();
+    write!(stdout, "{}", join(eval, &args)?)?;
+    stdout.flush()?;
+    Ok(Expression::Void)
+}
+
+fn writeln(eval: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    println!("{}", join(eval, &args)?);
+    Ok(Expression::Void)
+}
+
+fn eprint(eval: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    eprintln!("{}", join(eval, &args)?);
+    Ok(Expression::Void)
+}
@@ -0,0 +1,402 @@
+use std::{collections::BTreeMap, iter::Peekable, str::Chars};
+
+use crate::{error::Error, eval::Evaluator, expression::Expression};
+
+use super::{string, truthy, NativeFn};
+
+// parse is a special form reading ema code, so the json reader is exported as decode
+pub const FUNCTIONS: &[(&str, NativeFn)] = &[("decode", parse), ("stringify", stringify)];
+
+// reachable without importing std.json
+pub const GLOBALS: &[(&str, NativeFn)] = &[("json-parse", parse), ("json-stringify", stringify)];
+
+fn parse(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    let [text] = args.as_slice() else {
+        return Err(Error::Invalid(format!(
+            "json-parse expects 1 argument(s), got {}",
+            args.len()
+        )));
+    };
+
+    JsonParser::new(string("json-parse", text)?).parse_document()
+}
+
+// (json-stringify value) is compact, (json-stringify value indent) indents nested values
+// by indent spaces, true indents by 2
+fn stringify(eval: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    let indent = match args.as_slice() {
+        [_] => None,
+        [_, Expression::Number(width)] if *width >= 0.0 && width.fract() == 0.0 => {
+            Some(*width as usize)
+        }
+        [_, Expression::Number(width)] => {
+            return Err(Error::Invalid(format!("invalid json indent {}", width)))
+        }
+        [_, pretty] => truthy(pretty).then_some(2),
+        _ => {
+            return Err(Error::Invalid(format!(
+                "json-stringify expects 1 or 2 argument(s), got {}",
+                args.len()
+            )))
+        }
+    };
+
+    let mut out = String::new();
+    write_json(eval, &args[0], indent, 0, &mut vec![], &mut out)?;
+    Ok(Expression::String(out))
+}
+
+fn write_json(
+    eval: &mut Evaluator,
+    exp: &Expression,
+    indent: Option<usize>,
+    depth: usize,
+    seen: &mut Vec<usize>,
+    out: &mut String,
+) -> Result<(), Error> {
+    match exp {
+        Expression::Void => *out += "null",
+        Expression::Boolean(bool) => *out += &bool.to_string(),
+        Expression::Number(num) if num.is_finite() => *out += &num.to_string(),
+        Expression::String(str) => write_string(str, out),
+        Expression::DateTime(_) => write_string(&exp.to_string(), out),
+        Expression::List(items) => {
+            let items = items.iter().map(|item| (None, item.clone())).collect();
+            write_container(eval, ('[', ']'), items, indent, depth, seen, out)?;
+        }
+        Expression::Map(map) => {
+            let entries = map
+                .iter()
+                .map(|(key, value)| (Some(key.clone()), value.clone()))
+                .collect();
+            write_container(eval, ('{', '}'), entries, indent, depth, seen, out)?;
+        }
+        Expression::Object(obj) if obj.class.is_some() => {
+            if seen.contains(&obj.idx) {
+                return Err(Error::Invalid(format!(
+                    "cannot convert cyclic {} instance to json",
+                    obj.name
+                )));
+            }
+
+            seen.push(obj.idx);
+            let res = match eval.find_method(obj, "to-json") {
+                Some(to_json) => {
+                    let value = eval.call_function(&to_json, vec![exp.clone()])?;
+                    write_json(eval, &value, indent, depth, seen, out)
+                }
+                None => {
+                    let fields = eval
+                        .instance_fields(obj)
+                        .into_iter()
+                        .map(|(name, value)| (Some(name), value))
+                        .collect();
+                    write_container(eval, ('{', '}'), fields, indent, depth, seen, out)
+                }
+            };
+            seen.pop();
+            res?;
+        }
+        other => return Err(Error::Invalid(format!("cannot convert {} to json", other))),
+    }
+
+    Ok(())
+}
+
+fn write_container(
+    eval: &mut Evaluator,
+    (open, close): (char, char),
+    entries: Vec<(Option<String>, Expression)>,
+    indent: Option<usize>,
+    depth: usize,
+    seen: &mut Vec<usize>,
+    out: &mut String,
+) -> Result<(), Error> {
+    out.push(open);
+    if entries.is_empty() {
+        out.push(close);
+        return Ok(());
+    }
+
+    for (idx, (key, value)) in entries.iter().enumerate() {
+        if idx > 0 {
+            out.push(',');
+        }
+        if let Some(width) = indent {
+            out.push('\n');
+            *out += &" ".repeat(width * (depth + 1));
+        }
+        if let Some(key) = key {
+            write_string(key, out);
+            out.push(':');
+            if indent.is_some() {
+                out.push(' ');
+            }
+        }
+        write_json(eval, value, indent, depth + 1, seen, out)?;
+    }
+
+    if let Some(width) = indent {
+        out.push('\n');
+        *out += &" ".repeat(width * depth);
+    }
+    out.push(close);
+    Ok(())
+}
+
+fn write_string(str: &str, out: &mut String) {
+    out.push('"');
+    for ch in str.chars() {
+        match ch {
+            '"' => *out += "\\\"",
+            '\\' => *out += "\\\\",
+            '\n' => *out += "\\n",
+            '\r' => *out += "\\r",
+            '\t' => *out += "\\t",
+            ch if (ch as u32) < 0x20 => *out += &format!("\\u{:04x}", ch as u32),
+            ch => out.push(ch),
+        }
+    }
+    out.push('"');
+}
+
+// recursive descent parser keeping track of the position for error messages
+struct JsonParser<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> JsonParser<'a> {
+    fn new(text: &'a str) -> Self {
+        JsonParser {
+            chars: text.chars().peekable(),
+            line: 1,
+            column: 1,
+        }
+    }
+
+    fn error(&self, msg: &str) -> Error {
+        Error::Parse(format!(
+            "invalid json at line {}, column {}: {}",
+            self.line, self.column, msg
+        ))
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let ch = self.chars.next()?;
+        if ch == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(ch)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.chars.peek(), Some(' ' | '\t' | '\n' | '\r')) {
+            self.next();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), Error> {
+        match self.chars.peek().copied() {
+            Some(ch) if ch == expected => {
+                self.next();
+                Ok(())
+            }
+            Some(ch) => Err(self.error(&format!("expected '{}', found '{}'", expected, ch))),
+            None => Err(self.error(&format!("expected '{}', found end of input", expected))),
+        }
+    }
+
+    fn parse_document(&mut self) -> Result<Expression, Error> {
+        let value = self.parse_value()?;
+        self.skip_whitespace();
+        match self.chars.peek().copied() {
+            None => Ok(value),
+            Some(ch) => Err(self.error(&format!("unexpected '{}' after the value", ch))),
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Expression, Error> {
+        self.skip_whitespace();
+        match self.chars.peek().copied() {
+            Some('{') => self.parse_object(),
+            Some('[') => self.parse_array(),
+            Some('"') => Ok(Expression::String(self.parse_string()?)),
+            Some('-' | '0'..='9') => self.parse_number(),
+            Some('t') => self.parse_literal("true", Expression::Boolean(true)),
+            Some('f') => self.parse_literal("false", Expression::Boolean(false)),
+            Some('n') => self.parse_literal("null", Expression::Void),
+            Some(ch) => Err(self.error(&format!("unexpected '{}'", ch))),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn parse_literal(&mut self, literal: &str, value: Expression) -> Result<Expression, Error> {
+        for expected in literal.chars() {
+            match self.chars.peek() {
+                Some(ch) if *ch == expected => {
+                    self.next();
+                }
+                _ => return Err(self.error(&format!("invalid literal, expected {}", literal))),
+            }
+        }
+        Ok(value)
+    }
+
+    fn parse_object(&mut self) -> Result<Expression, Error> {
+        self.expect('{')?;
+        let mut entries = BTreeMap::new();
+
+        self.skip_whitespace();
+        if self.chars.peek() == Some(&'}') {
+            self.next();
+            return Ok(Expression::Map(entries));
+        }
+
+        loop {
+            self.skip_whitespace();
+            if self.chars.peek() != Some(&'"') {
+                return Err(self.error("expected a string key"));
+            }
+            let key = self.parse_string()?;
+
+            self.skip_whitespace();
+            self.expect(':')?;
+            let value = self.parse_value()?;
+            entries.insert(key, value);
+
+            self.skip_whitespace();
+            match self.chars.peek() {
+                Some(',') => {
+                    self.next();
+                }
+                Some('}') => {
+                    self.next();
+                    return Ok(Expression::Map(entries));
+                }
+                _ => return Err(self.error("expected ',' or '}' in object")),
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<Expression, Error> {
+        self.expect('[')?;
+        let mut items = vec![];
+
+        self.skip_whitespace();
+        if self.chars.peek() == Some(&']') {
+            self.next();
+            return Ok(Expression::List(items));
+        }
+
+        loop {
+            items.push(self.parse_value()?);
+
+            self.skip_whitespace();
+            match self.chars.peek() {
+                Some(',') => {
+                    self.next();
+                }
+                Some(']') => {
+                    self.next();
+                    return Ok(Expression::List(items));
+                }
+                _ => return Err(self.error("expected ',' or ']' in array")),
+            }
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, Error> {
+        self.expect('"')?;
+        let mut str = String::new();
+
+        loop {
+            match self.next() {
+                Some('"') => return Ok(str),
+                Some('\\') => match self.next() {
+                    Some('"') => str.push('"'),
+                    Some('\\') => str.push('\\'),
+                    Some('/') => str.push('/'),
+                    Some('b') => str.push('\u{8}'),
+                    Some('f') => str.push('\u{c}'),
+                    Some('n') => str.push('\n'),
+                    Some('r') => str.push('\r'),
+                    Some('t') => str.push('\t'),
+                    Some('u') => str.push(self.parse_unicode_escape()?),
+                    _ => return Err(self.error("invalid escape sequence")),
+                },
+                Some(ch) if (ch as u32) < 0x20 => {
+                    return Err(self.error("control character in string"))
+                }
+                Some(ch) => str.push(ch),
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+
+    // \uXXXX, surrogate pairs are combined into one character
+    fn parse_unicode_escape(&mut self) -> Result<char, Error> {
+        let high = self.parse_hex()?;
+        if !(0xd800..0xdc00).contains(&high) {
+            return char::from_u32(high).ok_or(self.error("invalid unicode escape"));
+        }
+
+        if self.next() != Some('\\') || self.next() != Some('u') {
+            return Err(self.error("unpaired surrogate in unicode escape"));
+        }
+        let low = self.parse_hex()?;
+        if !(0xdc00..0xe000).contains(&low) {
+            return Err(self.error("unpaired surrogate in unicode escape"));
+        }
+
+        char::from_u32(0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00))
+            .ok_or(self.error("invalid unicode escape"))
+    }
+
+    fn parse_hex(&mut self) -> Result<u32, Error> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let digit = self
+                .next()
+                .and_then(|ch| ch.to_digit(16))
+                .ok_or(self.error("invalid unicode escape"))?;
+            code = code * 16 + digit;
+        }
+        Ok(code)
+    }
+
+    fn parse_number(&mut self) -> Result<Expression, Error> {
+        let mut text = String::new();
+        while let Some(ch) = self.chars.peek() {
+            match ch {
+                '-' | '+' | '.' | 'e' | 'E' | '0'..='9' => {
+                    text.push(*ch);
+                    self.next();
+                }
+                _ => break,
+            }
+        }
+
+        // json does not allow leading zeros, a leading + or a bare dot
+        let digits = text.strip_prefix('-').unwrap_or(&text);
+        let leading_zero = digits.len() > 1
+            && digits.starts_with('0')
+            && digits[1..].starts_with(|ch: char| ch.is_ascii_digit());
+        let valid = digits.starts_with(|ch: char| ch.is_ascii_digit())
+            && !leading_zero
+            && !digits.contains(".e")
+            && !digits.contains(".E")
+            && !digits.ends_with('.');
+
+        // numbers too large for f64 would become inf, which json cannot write back
+        match text.parse::<f64>() {
+            Ok(num) if valid && num.is_finite() => Ok(Expression::Number(num)),
+            Ok(_) if valid => Err(self.error(&format!("number {} is out of range", text))),
+            _ => Err(self.error(&format!("invalid number {}", text))),
+        }
+    }
+}
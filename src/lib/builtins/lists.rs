@@ -0,0 +1,288 @@
+use std::cmp::Ordering;
+
+use crate::{error::Error, eval::Evaluator, expression::Expression};
+
+use super::{expect_args, integer, list, number, truthy, NativeFn};
+
+// most items a range may hold
+const MAX_RANGE: usize = 10_000_000;
+
+pub const FUNCTIONS: &[(&str, NativeFn)] = &[
+    ("length", length),
+    ("nth", nth),
+    ("first", first),
+    ("last", last),
+    ("rest", rest),
+    ("push", push),
+    ("concat", concat),
+    ("slice", slice),
+    ("reverse", reverse),
+    ("range", range),
+    ("map", map),
+    ("filter", filter),
+    ("reduce", reduce),
+    ("sort", sort),
+    ("contains?", contains),
+    ("index-of", index_of),
+    ("empty?", empty),
+];
+
+fn length(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("length", &args, 1)?;
+    Ok(Expression::Number(list("length", &args[0])?.len() as f64))
+}
+
+fn nth(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("nth", &args, 2)?;
+    let items = list("nth", &args[0])?;
+    let idx = integer("nth", &args[1])?;
+
+    usize::try_from(idx)
+        .ok()
+        .and_then(|idx| items.get(idx))
+        .cloned()
+        .ok_or(Error::Reason(format!(
+            "index {} out of bounds for list of length {}",
+            idx,
+            items.len()
+        )))
+}
+
+fn first(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("first", &args, 1)?;
+    Ok(list("first", &args[0])?
+        .first()
+        .cloned()
+        .unwrap_or(Expression::Void))
+}
+
+fn last(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("last", &args, 1)?;
+    Ok(list("last", &args[0])?
+        .last()
+        .cloned()
+        .unwrap_or(Expression::Void))
+}
+
+fn rest(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("rest", &args, 1)?;
+    let items = list("rest", &args[0])?;
+    Ok(Expression::List(items.iter().skip(1).cloned().collect()))
+}
+
+// lists are values, push returns a new list
+fn push(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("push", &args, 2)?;
+    let mut items = list("push", &args[0])?.to_vec();
+    items.push(args[1].clone());
+    Ok(Expression::List(items))
+}
+
+fn concat(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    let mut items = vec![];
+    for arg in &args {
+        items.extend_from_slice(list("concat", arg)?);
+    }
+
+    Ok(Expression::List(items))
+}
+
+// (slice l start end), end is exclusive
+fn slice(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("slice", &args, 3)?;
+    let items = list("slice", &args[0])?;
+    let (start, end) = (integer("slice", &args[1])?, integer("slice", &args[2])?);
+
+    if start < 0 || end < start || end > items.len() as i64 {
+        return Err(Error::Reason(format!(
+            "slice range {}..{} out of bounds for list of length {}",
+            start,
+            end,
+            items.len()
+        )));
+    }
+
+    Ok(Expression::List(
+        items[start as usize..end as usize].to_vec(),
+    ))
+}
+
+fn reverse(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("reverse", &args, 1)?;
+    Ok(Expression::List(
+        list("reverse", &args[0])?.iter().rev().cloned().collect(),
+    ))
+}
+
+// (range end), (range start end) or (range start end step)
+fn range(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    let (start, end, step) = match args.as_slice() {
+        [end] => (0.0, number("range", end)?, 1.0),
+        [start, end] => (number("range", start)?, number("range", end)?, 1.0),
+        [start, end, step] => (
+            number("range", start)?,
+            number("range", end)?,
+            number("range", step)?,
+        ),
+        _ => {
+            return Err(Error::Invalid(format!(
+                "range expects 1 to 3 argument(s), got {}",
+                args.len()
+            )))
+        }
+    };
+
+    if step == 0.0 {
+        return Err(Error::Reason("range step cannot be zero".to_string()));
+    }
+
+    // counted up front, adding step to a large number can leave it unchanged
+    let count = ((end - start) / step).ceil();
+    if count > MAX_RANGE as f64 {
+        return Err(Error::Reason(format!(
+            "range from {} to {} by {} has more than {} items",
+            start, end, step, MAX_RANGE
+        )));
+    }
+
+    let count = if count > 0.0 { count as usize } else { 0 };
+    Ok(Expression::List(
+        (0..count)
+            .map(|idx| Expression::Number(start + idx as f64 * step))
+            .collect(),
+    ))
+}
+
+fn map(eval: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("map", &args, 2)?;
+    let items = list("map", &args[0])?
+        .iter()
+        .map(|item| eval.call_function(&args[1], vec![item.clone()]))
+        .collect::<Result<Vec<Expression>, Error>>()?;
+
+    Ok(Expression::List(items))
+}
+
+fn filter(eval: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("filter", &args, 2)?;
+    let mut items = vec![];
+    for item in list("filter", &args[0])? {
+        if truthy(&eval.call_function(&args[1], vec![item.clone()])?) {
+            items.push(item.clone());
+        }
+    }
+
+    Ok(Expression::List(items))
+}
+
+// (reduce l func initial) folds from the left with (func acc item)
+fn reduce(eval: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("reduce", &args, 3)?;
+    let mut acc = args[2].clone();
+    for item in list("reduce", &args[0])? {
+        acc = eval.call_function(&args[1], vec![acc, item.clone()])?;
+    }
+
+    Ok(acc)
+}
+
+// (sort l) orders numbers or strings, (sort l less) uses a comparator returning a boolean
+fn sort(eval: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    let (items, less) = match args.as_slice() {
+        [items] => (list("sort", items)?, None),
+        [items, less] => (list("sort", items)?, Some(less)),
+        _ => {
+            return Err(Error::Invalid(format!(
+                "sort expects 1 or 2 argument(s), got {}",
+                args.len()
+            )))
+        }
+    };
+
+    // merge sort keeps the order stable and lets the comparator fail
+    let mut sorted = items
+        .iter()
+        .cloned()
+        .map(|item| vec![item])
+        .collect::<Vec<_>>();
+    while sorted.len() > 1 {
+        let mut merged = vec![];
+        let mut runs = sorted.into_iter();
+        while let Some(left) = runs.next() {
+            match runs.next() {
+                Some(right) => merged.push(merge(eval, left, right, less)?),
+                None => merged.push(left),
+            }
+        }
+        sorted = merged;
+    }
+
+    Ok(Expression::List(sorted.pop().unwrap_or_default()))
+}
+
+fn merge(
+    eval: &mut Evaluator,
+    left: Vec<Expression>,
+    right: Vec<Expression>,
+    less: Option<&Expression>,
+) -> Result<Vec<Expression>, Error> {
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    let mut left = left.into_iter().peekable();
+    let mut right = right.into_iter().peekable();
+
+    while let (Some(a), Some(b)) = (left.peek(), right.peek()) {
+        let right_first = match less {
+            Some(less) => truthy(&eval.call_function(less, vec![b.clone(), a.clone()])?),
+            None => compare(a, b)? == Ordering::Greater,
+        };
+
+        if right_first {
+            merged.push(right.next().unwrap());
+        } else {
+            merged.push(left.next().unwrap());
+        }
+    }
+
+    merged.extend(left);
+    merged.extend(right);
+    Ok(merged)
+}
+
+fn compare(a: &Expression, b: &Expression) -> Result<Ordering, Error> {
+    match (a, b) {
+        (Expression::Number(a), Expression::Number(b)) => {
+            Ok(a.partial_cmp(b).unwrap_or(Ordering::Equal))
+        }
+        (Expression::String(a), Expression::String(b)) => Ok(a.cmp(b)),
+        _ => Err(Error::Invalid(format!(
+            "cannot compare {} and {} without a comparator",
+            a, b
+        ))),
+    }
+}
+
+fn contains(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("contains?", &args, 2)?;
+    Ok(Expression::Boolean(
+        list("contains?", &args[0])?.contains(&args[1]),
+    ))
+}
+
+// index of the first equal item, -1 when missing
+fn index_of(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("index-of", &args, 2)?;
+    Ok(Expression::Number(
+        match list("index-of", &args[0])?
+            .iter()
+            .position(|item| item == &args[1])
+        {
+            Some(idx) => idx as f64,
+            None => -1.0,
+        },
+    ))
+}
+
+fn empty(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("empty?", &args, 1)?;
+    Ok(Expression::Boolean(list("empty?", &args[0])?.is_empty()))
+}
@@ -0,0 +1,130 @@
+use std::collections::BTreeMap;
+
+use crate::{error::Error, eval::Evaluator, expression::Expression};
+
+use super::{expect_args, list, map, string, NativeFn};
+
+pub const FUNCTIONS: &[(&str, NativeFn)] = &[
+    ("make", make),
+    ("lookup", lookup),
+    ("put", put),
+    ("remove", remove),
+    ("has?", has),
+    ("keys", keys),
+    ("values", values),
+    ("entries", entries),
+    ("length", length),
+    ("merge", merge),
+];
+
+// (make "a" 1 "b" 2) builds a map from key value pairs
+fn make(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    if !args.len().is_multiple_of(2) {
+        return Err(Error::Invalid("make expects key value pairs".to_string()));
+    }
+
+    let mut entries = BTreeMap::new();
+    for pair in args.chunks(2) {
+        entries.insert(string("make", &pair[0])?.to_string(), pair[1].clone());
+    }
+
+    Ok(Expression::Map(entries))
+}
+
+// (lookup m key) or (lookup m key default), nil when missing without a default
+fn lookup(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    let (entries, key, default) = match args.as_slice() {
+        [entries, key] => (entries, key, Expression::Void),
+        [entries, key, default] => (entries, key, default.clone()),
+        _ => {
+            return Err(Error::Invalid(format!(
+                "lookup expects 2 or 3 argument(s), got {}",
+                args.len()
+            )))
+        }
+    };
+
+    Ok(map("lookup", entries)?
+        .get(string("lookup", key)?)
+        .cloned()
+        .unwrap_or(default))
+}
+
+// maps are values, put and remove return a new map
+fn put(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("put", &args, 3)?;
+    let mut entries = map("put", &args[0])?.clone();
+    entries.insert(string("put", &args[1])?.to_string(), args[2].clone());
+    Ok(Expression::Map(entries))
+}
+
+fn remove(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("remove", &args, 2)?;
+    let mut entries = map("remove", &args[0])?.clone();
+    entries.remove(string("remove", &args[1])?);
+    Ok(Expression::Map(entries))
+}
+
+fn has(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("has?", &args, 2)?;
+    Ok(Expression::Boolean(
+        map("has?", &args[0])?.contains_key(string("has?", &args[1])?),
+    ))
+}
+
+fn keys(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("keys", &args, 1)?;
+    Ok(Expression::List(
+        map("keys", &args[0])?
+            .keys()
+            .map(|key| Expression::String(key.clone()))
+            .collect(),
+    ))
+}
+
+fn values(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("values", &args, 1)?;
+    Ok(Expression::List(
+        map("values", &args[0])?.values().cloned().collect(),
+    ))
+}
+
+// list of (key value) pairs ordered by key
+fn entries(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("entries", &args, 1)?;
+    Ok(Expression::List(
+        map("entries", &args[0])?
+            .iter()
+            .map(|(key, value)| {
+                Expression::List(vec![Expression::String(key.clone()), value.clone()])
+            })
+            .collect(),
+    ))
+}
+
+fn length(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("length", &args, 1)?;
+    Ok(Expression::Number(map("length", &args[0])?.len() as f64))
+}
+
+// later maps win on duplicated keys, a list of pairs is accepted as well
+fn merge(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    let mut merged = BTreeMap::new();
+    for arg in &args {
+        match arg {
+            Expression::Map(entries) => merged.extend(entries.clone()),
+            other => {
+                for pair in list("merge", other)? {
+                    let [key, value] = list("merge", pair)? else {
+                        return Err(Error::Invalid(
+                            "merge expects (key value) pairs".to_string(),
+                        ));
+                    };
+                    merged.insert(string("merge", key)?.to_string(), value.clone());
+                }
+            }
+        }
+    }
+
+    Ok(Expression::Map(merged))
+}
@@ -0,0 +1,384 @@
+use std::f64::consts;
+
+use crate::{error::Error, eval::Evaluator, expression::Expression};
+
+use super::{expect_args, integer, number, NativeFn, MAX_SAFE_INTEGER};
+
+pub const CONSTANTS: &[(&str, f64)] = &[
+    ("pi", consts::PI),
+    ("tau", consts::TAU),
+    ("e", consts::E),
+    ("infinity", f64::INFINITY),
+    ("nan", f64::NAN),
+    ("epsilon", f64::EPSILON),
+    ("max-safe-integer", MAX_SAFE_INTEGER),
+];
+
+pub const FUNCTIONS: &[(&str, NativeFn)] = &[
+    ("abs", abs),
+    ("sign", sign),
+    ("sqrt", sqrt),
+    ("cbrt", cbrt),
+    ("pow", pow),
+    ("exp", exp),
+    ("exp2", exp2),
+    ("ln", ln),
+    ("log", log),
+    ("log2", log2),
+    ("log10", log10),
+    ("sin", sin),
+    ("cos", cos),
+    ("tan", tan),
+    ("asin", asin),
+    ("acos", acos),
+    ("atan", atan),
+    ("atan2", atan2),
+    ("sinh", sinh),
+    ("cosh", cosh),
+    ("tanh", tanh),
+    ("asinh", asinh),
+    ("acosh", acosh),
+    ("atanh", atanh),
+    ("hypot", hypot),
+    ("floor", floor),
+    ("ceil", ceil),
+    ("round", round),
+    ("trunc", trunc),
+    ("fract", fract),
+    ("min", min),
+    ("max", max),
+    ("clamp", clamp),
+    ("gcd", gcd),
+    ("lcm", lcm),
+    ("to-degrees", to_degrees),
+    ("to-radians", to_radians),
+    ("nan?", is_nan),
+    ("infinite?", is_infinite),
+    ("finite?", is_finite),
+    ("integer?", is_integer),
+];
+
+fn unary(name: &str, args: &[Expression]) -> Result<f64, Error> {
+    expect_args(name, args, 1)?;
+    number(name, &args[0])
+}
+
+fn domain_error(name: &str, x: f64, expected: &str) -> Error {
+    Error::Reason(format!(
+        "math domain error: {} of {}, expected {}",
+        name, x, expected
+    ))
+}
+
+fn abs(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    Ok(Expression::Number(unary("abs", &args)?.abs()))
+}
+
+// -1, 0 or 1, nan stays nan
+fn sign(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    let x = unary("sign", &args)?;
+    Ok(Expression::Number(if x == 0.0 { 0.0 } else { x.signum() }))
+}
+
+fn sqrt(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    let x = unary("sqrt", &args)?;
+    if x < 0.0 {
+        return Err(domain_error("sqrt", x, "a non-negative number"));
+    }
+    Ok(Expression::Number(x.sqrt()))
+}
+
+fn cbrt(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    Ok(Expression::Number(unary("cbrt", &args)?.cbrt()))
+}
+
+fn pow(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("pow", &args, 2)?;
+    let (base, exponent) = (number("pow", &args[0])?, number("pow", &args[1])?);
+    if base < 0.0 && exponent.fract() != 0.0 {
+        return Err(domain_error(
+            "pow",
+            base,
+            "a non-negative base for a fractional exponent",
+        ));
+    }
+    Ok(Expression::Number(base.powf(exponent)))
+}
+
+fn exp(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    Ok(Expression::Number(unary("exp", &args)?.exp()))
+}
+
+fn exp2(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    Ok(Expression::Number(unary("exp2", &args)?.exp2()))
+}
+
+// logarithms of zero are -infinity, negative numbers are a domain error
+fn logarithm(name: &str, x: f64, log: fn(f64) -> f64) -> Result<Expression, Error> {
+    if x < 0.0 {
+        return Err(domain_error(name, x, "a non-negative number"));
+    }
+    Ok(Expression::Number(log(x)))
+}
+
+fn ln(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    logarithm("ln", unary("ln", &args)?, f64::ln)
+}
+
+// (log x) is the natural logarithm, (log x base) uses the given base
+fn log(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    match args.as_slice() {
+        [x] => logarithm("log", number("log", x)?, f64::ln),
+        [x, base] => {
+            let (x, base) = (number("log", x)?, number("log", base)?);
+            if base <= 0.0 || base == 1.0 {
+                return Err(domain_error(
+                    "log base",
+                    base,
+                    "a positive number other than 1",
+                ));
+            }
+            match logarithm("log", x, f64::ln)? {
+                Expression::Number(ln) => Ok(Expression::Number(ln / base.ln())),
+                other => Ok(other),
+            }
+        }
+        _ => Err(Error::Invalid(format!(
+            "log expects 1 or 2 argument(s), got {}",
+            args.len()
+        ))),
+    }
+}
+
+fn log2(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    logarithm("log2", unary("log2", &args)?, f64::log2)
+}
+
+fn log10(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    logarithm("log10", unary("log10", &args)?, f64::log10)
+}
+
+fn sin(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    Ok(Expression::Number(unary("sin", &args)?.sin()))
+}
+
+fn cos(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    Ok(Expression::Number(unary("cos", &args)?.cos()))
+}
+
+fn tan(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    Ok(Expression::Number(unary("tan", &args)?.tan()))
+}
+
+fn asin(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    let x = unary("asin", &args)?;
+    if !(-1.0..=1.0).contains(&x) {
+        return Err(domain_error("asin", x, "a number between -1 and 1"));
+    }
+    Ok(Expression::Number(x.asin()))
+}
+
+fn acos(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    let x = unary("acos", &args)?;
+    if !(-1.0..=1.0).contains(&x) {
+        return Err(domain_error("acos", x, "a number between -1 and 1"));
+    }
+    Ok(Expression::Number(x.acos()))
+}
+
+fn atan(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    Ok(Expression::Number(unary("atan", &args)?.atan()))
+}
+
+// (atan2 y x)
+fn atan2(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("atan2", &args, 2)?;
+    Ok(Expression::Number(
+        number("atan2", &args[0])?.atan2(number("atan2", &args[1])?),
+    ))
+}
+
+fn sinh(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    Ok(Expression::Number(unary("sinh", &args)?.sinh()))
+}
+
+fn cosh(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    Ok(Expression::Number(unary("cosh", &args)?.cosh()))
+}
+
+fn tanh(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    Ok(Expression::Number(unary("tanh", &args)?.tanh()))
+}
+
+fn asinh(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    Ok(Expression::Number(unary("asinh", &args)?.asinh()))
+}
+
+fn acosh(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    let x = unary("acosh", &args)?;
+    if x < 1.0 {
+        return Err(domain_error(
+            "acosh",
+            x,
+            "a number greater than or equal to 1",
+        ));
+    }
+    Ok(Expression::Number(x.acosh()))
+}
+
+fn atanh(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    let x = unary("atanh", &args)?;
+    if !(-1.0..=1.0).contains(&x) {
+        return Err(domain_error("atanh", x, "a number between -1 and 1"));
+    }
+    Ok(Expression::Number(x.atanh()))
+}
+
+fn hypot(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("hypot", &args, 2)?;
+    Ok(Expression::Number(
+        number("hypot", &args[0])?.hypot(number("hypot", &args[1])?),
+    ))
+}
+
+fn floor(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    Ok(Expression::Number(unary("floor", &args)?.floor()))
+}
+
+fn ceil(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    Ok(Expression::Number(unary("ceil", &args)?.ceil()))
+}
+
+// (round x) rounds half away from zero, (round x digits) keeps that many decimals
+fn round(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    match args.as_slice() {
+        [x] => Ok(Expression::Number(number("round", x)?.round())),
+        [x, digits] => {
+            let (x, digits) = (number("round", x)?, integer("round", digits)?);
+            let scale = 10f64.powi(digits.clamp(-308, 308) as i32);
+            Ok(Expression::Number((x * scale).round() / scale))
+        }
+        _ => Err(Error::Invalid(format!(
+            "round expects 1 or 2 argument(s), got {}",
+            args.len()
+        ))),
+    }
+}
+
+fn trunc(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    Ok(Expression::Number(unary("trunc", &args)?.trunc()))
+}
+
+fn fract(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    Ok(Expression::Number(unary("fract", &args)?.fract()))
+}
+
+// numbers given as arguments or as a single list, nan when any of them is nan
+fn numbers(name: &str, args: &[Expression]) -> Result<Vec<f64>, Error> {
+    let items = match args {
+        [Expression::List(items)] => items.as_slice(),
+        _ => args,
+    };
+    if items.is_empty() {
+        return Err(Error::Invalid(format!(
+            "{} expects at least one number",
+            name
+        )));
+    }
+
+    items.iter().map(|item| number(name, item)).collect()
+}
+
+fn min(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    let nums = numbers("min", &args)?;
+    Ok(Expression::Number(match nums.iter().any(|x| x.is_nan()) {
+        true => f64::NAN,
+        false => nums.into_iter().fold(f64::INFINITY, f64::min),
+    }))
+}
+
+fn max(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    let nums = numbers("max", &args)?;
+    Ok(Expression::Number(match nums.iter().any(|x| x.is_nan()) {
+        true => f64::NAN,
+        false => nums.into_iter().fold(f64::NEG_INFINITY, f64::max),
+    }))
+}
+
+// (clamp x low high)
+fn clamp(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("clamp", &args, 3)?;
+    let (x, low, high) = (
+        number("clamp", &args[0])?,
+        number("clamp", &args[1])?,
+        number("clamp", &args[2])?,
+    );
+    if low.is_nan() || high.is_nan() || low > high {
+        return Err(Error::Reason(format!(
+            "clamp bounds {} and {} are not ordered",
+            low, high
+        )));
+    }
+    Ok(Expression::Number(x.clamp(low, high)))
+}
+
+// on magnitudes so that i64::MIN cannot overflow
+fn gcd_of(a: i64, b: i64) -> u64 {
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+fn gcd(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("gcd", &args, 2)?;
+    Ok(Expression::Number(
+        gcd_of(integer("gcd", &args[0])?, integer("gcd", &args[1])?) as f64,
+    ))
+}
+
+fn lcm(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("lcm", &args, 2)?;
+    let (a, b) = (integer("lcm", &args[0])?, integer("lcm", &args[1])?);
+    if a == 0 || b == 0 {
+        return Ok(Expression::Number(0.0));
+    }
+
+    // the product of two safe integers fits in u128, the result must be a safe integer again
+    let lcm = (a.unsigned_abs() / gcd_of(a, b)) as u128 * b.unsigned_abs() as u128;
+    if lcm > MAX_SAFE_INTEGER as u128 {
+        return Err(Error::Reason(format!(
+            "math domain error: lcm of {} and {} is above max-safe-integer",
+            a, b
+        )));
+    }
+    Ok(Expression::Number(lcm as f64))
+}
+
+fn to_degrees(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    Ok(Expression::Number(unary("to-degrees", &args)?.to_degrees()))
+}
+
+fn to_radians(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    Ok(Expression::Number(unary("to-radians", &args)?.to_radians()))
+}
+
+fn is_nan(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    Ok(Expression::Boolean(unary("nan?", &args)?.is_nan()))
+}
+
+fn is_infinite(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    Ok(Expression::Boolean(
+        unary("infinite?", &args)?.is_infinite(),
+    ))
+}
+
+fn is_finite(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    Ok(Expression::Boolean(unary("finite?", &args)?.is_finite()))
+}
+
+fn is_integer(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    let x = unary("integer?", &args)?;
+    Ok(Expression::Boolean(x.is_finite() && x.fract() == 0.0))
+}
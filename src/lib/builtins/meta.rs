@@ -0,0 +1,73 @@
+use crate::{
+    environment::Environment, error::Error, eval::Evaluator, expression::Expression, parser::parse,
+    transform::transform_program_to_block,
+};
+
+use super::{expect_args, list, string, NativeFn};
+
+pub const FUNCTIONS: &[(&str, NativeFn)] = &[
+    ("list", list_of),
+    ("apply", apply),
+    ("funcall", funcall),
+    ("eval", eval_code),
+    ("parse", parse_source),
+];
+
+fn list_of(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    Ok(Expression::List(args))
+}
+
+// (apply func args) calls func with the items of args
+fn apply(eval: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("apply", &args, 2)?;
+    let items = list("apply", &args[1])?.to_vec();
+    eval.call_function(&args[0], items)
+}
+
+// (funcall func args...)
+fn funcall(eval: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    let Some((func, rest)) = args.split_first() else {
+        return Err(Error::Invalid(
+            "funcall expects at least 1 argument(s), got 0".to_string(),
+        ));
+    };
+
+    eval.call_function(func, rest.to_vec())
+}
+
+// (eval exp), (eval exp "current"), (eval exp "global") or (eval exp Module), by default exp
+// runs in the environment eval is called from
+fn eval_code(eval: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    let (code, target) = match args.as_slice() {
+        [code] => (code, None),
+        [code, target] => (code, Some(target)),
+        _ => {
+            return Err(Error::Invalid(format!(
+                "eval expects 1 or 2 argument(s), got {}",
+                args.len()
+            )))
+        }
+    };
+
+    let caller = eval.caller_env()?;
+    let mut env = match target {
+        None => caller,
+        Some(Expression::String(name)) if name == "current" => caller,
+        Some(Expression::String(name)) if name == "global" => Environment::global(&caller),
+        Some(Expression::Object(obj)) => eval.object_env(obj)?,
+        Some(other) => {
+            return Err(Error::Invalid(format!(
+                "cannot evaluate in environment of {}",
+                other
+            )))
+        }
+    };
+
+    eval.eval_exp(code, &mut env)
+}
+
+// several forms are read as one block so that eval runs all of them
+fn parse_source(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("parse", &args, 1)?;
+    transform_program_to_block(&parse(string("parse", &args[0])?)?)
+}
@@ -0,0 +1,158 @@
+use std::collections::BTreeMap;
+
+use crate::{error::Error, eval::Evaluator, expression::Expression};
+
+pub mod format;
+pub mod fs;
+pub mod input;
+pub mod io;
+pub mod json;
+pub mod lists;
+pub mod maps;
+pub mod math;
+pub mod meta;
+pub mod process;
+pub mod random;
+pub mod reflection;
+pub mod regex;
+pub mod strings;
+pub mod test;
+pub mod time;
+
+// native functions receive already evaluated arguments
+pub type NativeFn = fn(&mut Evaluator, Vec<Expression>) -> Result<Expression, Error>;
+
+// natives bound into the std module of the same name
+pub fn functions(module: &str) -> &'static [(&'static str, NativeFn)] {
+    match module {
+        "fs" => fs::FUNCTIONS,
+        "io" => io::FUNCTIONS,
+        "json" => json::FUNCTIONS,
+        "lists" => lists::FUNCTIONS,
+        "maps" => maps::FUNCTIONS,
+        "math" => math::FUNCTIONS,
+        "random" => random::FUNCTIONS,
+        "regex" => regex::FUNCTIONS,
+        "strings" => strings::FUNCTIONS,
+        "test" => test::FUNCTIONS,
+        "time" => time::FUNCTIONS,
+        _ => &[],
+    }
+}
+
+// numeric constants bound into the std module of the same name
+pub fn constants(module: &str) -> &'static [(&'static str, f64)] {
+    match module {
+        "math" => math::CONSTANTS,
+        _ => &[],
+    }
+}
+
+// natives reachable without an import
+const GLOBALS: &[&[(&str, NativeFn)]] = &[
+    meta::FUNCTIONS,
+    reflection::FUNCTIONS,
+    process::FUNCTIONS,
+    input::FUNCTIONS,
+    json::GLOBALS,
+    format::FUNCTIONS,
+    test::FUNCTIONS,
+];
+
+pub fn global(name: &str) -> Option<NativeFn> {
+    GLOBALS
+        .iter()
+        .flat_map(|natives| natives.iter())
+        .find(|(native, _)| *native == name)
+        .map(|(_, native)| *native)
+}
+
+// native function by its qualified name, e.g. strings.upper, or a global one like getenv
+pub fn lookup(name: &str) -> Option<NativeFn> {
+    let Some((module, func)) = name.split_once('.') else {
+        return global(name);
+    };
+
+    functions(module)
+        .iter()
+        .find(|(native, _)| *native == func)
+        .map(|(_, native)| *native)
+}
+
+pub(crate) fn expect_args(name: &str, args: &[Expression], count: usize) -> Result<(), Error> {
+    if args.len() != count {
+        return Err(Error::Invalid(format!(
+            "{} expects {} argument(s), got {}",
+            name,
+            count,
+            args.len()
+        )));
+    }
+
+    Ok(())
+}
+
+pub(crate) fn number(name: &str, exp: &Expression) -> Result<f64, Error> {
+    match exp {
+        Expression::Number(num) => Ok(*num),
+        other => Err(Error::Invalid(format!(
+            "{} expects a number, got {}",
+            name, other
+        ))),
+    }
+}
+
+pub(crate) fn string<'a>(name: &str, exp: &'a Expression) -> Result<&'a str, Error> {
+    match exp {
+        Expression::String(str) => Ok(str),
+        other => Err(Error::Invalid(format!(
+            "{} expects a string, got {}",
+            name, other
+        ))),
+    }
+}
+
+pub(crate) fn list<'a>(name: &str, exp: &'a Expression) -> Result<&'a [Expression], Error> {
+    match exp {
+        Expression::List(list) => Ok(list),
+        Expression::Void => Ok(&[]),
+        other => Err(Error::Invalid(format!(
+            "{} expects a list, got {}",
+            name, other
+        ))),
+    }
+}
+
+pub(crate) fn map<'a>(
+    name: &str,
+    exp: &'a Expression,
+) -> Result<&'a BTreeMap<String, Expression>, Error> {
+    match exp {
+        Expression::Map(map) => Ok(map),
+        other => Err(Error::Invalid(format!(
+            "{} expects a map, got {}",
+            name, other
+        ))),
+    }
+}
+
+// largest integer below which every integer is exactly representable as f64
+pub(crate) const MAX_SAFE_INTEGER: f64 = 9007199254740991.0;
+
+// whole numbers used as indexes and counts, larger magnitudes than max-safe-integer are
+// rejected since they would not survive the conversion to i64 and back
+pub(crate) fn integer(name: &str, exp: &Expression) -> Result<i64, Error> {
+    let num = number(name, exp)?;
+    if !num.is_finite() || num.fract() != 0.0 || num.abs() > MAX_SAFE_INTEGER {
+        return Err(Error::Invalid(format!(
+            "{} expects an integer, got {}",
+            name, num
+        )));
+    }
+
+    Ok(num as i64)
+}
+
+pub(crate) fn truthy(exp: &Expression) -> bool {
+    !matches!(exp, Expression::Void | Expression::Boolean(false))
+}
@@ -0,0 +1,74 @@
+use std::env;
+
+use crate::{error::Error, eval::Evaluator, expression::Expression};
+
+use super::{expect_args, integer, string, NativeFn};
+
+pub const FUNCTIONS: &[(&str, NativeFn)] = &[
+    ("getenv", getenv),
+    ("setenv", setenv),
+    ("unsetenv", unsetenv),
+    ("exit", exit),
+];
+
+// (getenv name) is nil when the variable is not set, (getenv name default) falls back to default
+fn getenv(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    let (name, default) = match args.as_slice() {
+        [name] => (string("getenv", name)?, Expression::Void),
+        [name, default] => (string("getenv", name)?, default.clone()),
+        _ => {
+            return Err(Error::Invalid(format!(
+                "getenv expects 1 or 2 argument(s), got {}",
+                args.len()
+            )))
+        }
+    };
+
+    Ok(env::var(name).map(Expression::String).unwrap_or(default))
+}
+
+fn setenv(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("setenv", &args, 2)?;
+    let (name, value) = (string("setenv", &args[0])?, string("setenv", &args[1])?);
+    if name.is_empty() || name.contains(['=', '\0']) || value.contains('\0') {
+        return Err(Error::Invalid(format!(
+            "invalid environment variable {}",
+            name
+        )));
+    }
+
+    env::set_var(name, value);
+    Ok(Expression::Void)
+}
+
+fn unsetenv(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("unsetenv", &args, 1)?;
+    let name = string("unsetenv", &args[0])?;
+    if name.is_empty() || name.contains(['=', '\0']) {
+        return Err(Error::Invalid(format!(
+            "invalid environment variable {}",
+            name
+        )));
+    }
+
+    env::remove_var(name);
+    Ok(Expression::Void)
+}
+
+// (exit) or (exit code) unwinds the program, the runner exits the process with code
+fn exit(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    let code = match args.as_slice() {
+        [] => 0,
+        [code] => integer("exit", code)?,
+        _ => {
+            return Err(Error::Invalid(format!(
+                "exit expects 0 or 1 argument(s), got {}",
+                args.len()
+            )))
+        }
+    };
+
+    Err(Error::Exit(
+        code.clamp(i32::MIN as i64, i32::MAX as i64) as i32
+    ))
+}
@@ -0,0 +1,121 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{error::Error, eval::Evaluator, expression::Expression};
+
+use super::{expect_args, integer, list, NativeFn};
+
+pub const FUNCTIONS: &[(&str, NativeFn)] = &[
+    ("random", random),
+    ("random-int", random_int),
+    ("choice", choice),
+    ("shuffle", shuffle),
+    ("seed", seed),
+];
+
+// splitmix64, only integer arithmetic so a seed gives the same numbers on every platform
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    // uniform in [0, 1) with 53 random bits
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    // uniform in [0, bound), rejecting the biased tail
+    pub fn below(&mut self, bound: u64) -> u64 {
+        let zone = u64::MAX - u64::MAX % bound;
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return value % bound;
+            }
+        }
+    }
+}
+
+// unseeded evaluators start from the clock
+impl Default for Rng {
+    fn default() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos() as u64)
+            .unwrap_or_default();
+
+        Rng::new(nanos)
+    }
+}
+
+fn random(eval: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("random", &args, 0)?;
+    Ok(Expression::Number(eval.rng().next_f64()))
+}
+
+// (random-int low high) with both bounds included
+fn random_int(eval: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("random-int", &args, 2)?;
+    let (low, high) = (
+        integer("random-int", &args[0])?,
+        integer("random-int", &args[1])?,
+    );
+    if low > high {
+        return Err(Error::Reason(format!(
+            "random-int range {}..{} is empty",
+            low, high
+        )));
+    }
+
+    let span = high.abs_diff(low).wrapping_add(1);
+    let offset = match span {
+        0 => eval.rng().next_u64(),
+        _ => eval.rng().below(span),
+    };
+
+    Ok(Expression::Number(low.wrapping_add(offset as i64) as f64))
+}
+
+fn choice(eval: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("choice", &args, 1)?;
+    let items = list("choice", &args[0])?;
+    if items.is_empty() {
+        return Err(Error::Reason(
+            "cannot choose from an empty list".to_string(),
+        ));
+    }
+
+    let idx = eval.rng().below(items.len() as u64) as usize;
+    Ok(items[idx].clone())
+}
+
+// returns a shuffled copy, the argument is left untouched
+fn shuffle(eval: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("shuffle", &args, 1)?;
+    let mut items = list("shuffle", &args[0])?.to_vec();
+
+    for idx in (1..items.len()).rev() {
+        let other = eval.rng().below(idx as u64 + 1) as usize;
+        items.swap(idx, other);
+    }
+
+    Ok(Expression::List(items))
+}
+
+fn seed(eval: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("seed", &args, 1)?;
+    eval.set_seed(integer("seed", &args[0])? as u64);
+    Ok(Expression::Void)
+}
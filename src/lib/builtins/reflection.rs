@@ -0,0 +1,128 @@
+use crate::{
+    error::Error,
+    eval::Evaluator,
+    expression::{Expression, Object},
+};
+
+use super::{expect_args, string, NativeFn};
+
+pub const FUNCTIONS: &[(&str, NativeFn)] = &[
+    ("instance-of?", instance_of),
+    ("class-of", class_of),
+    ("class-name", class_name),
+    ("parent-class", parent_class),
+    ("fields", fields),
+    ("methods", methods),
+    ("has-prop?", has_prop),
+];
+
+fn object(exp: &Expression) -> Result<&Object, Error> {
+    match exp {
+        Expression::Object(obj) => Ok(obj),
+        other => Err(Error::Reason(format!(
+            "{} is not a instance of a class",
+            other
+        ))),
+    }
+}
+
+// (instance-of? value Class) is true when Class is the class of value or one of its parents
+fn instance_of(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("instance-of?", &args, 2)?;
+    let class = object(&args[1])?;
+    let Expression::Object(instance) = &args[0] else {
+        return Ok(Expression::Boolean(false));
+    };
+
+    let mut current = instance.class.clone();
+    while let Some(obj) = current {
+        if obj.borrow().idx == class.idx {
+            return Ok(Expression::Boolean(true));
+        }
+        current = obj.borrow().parent.clone();
+    }
+
+    Ok(Expression::Boolean(false))
+}
+
+fn class_of(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("class-of", &args, 1)?;
+    match &args[0] {
+        Expression::Object(Object {
+            class: Some(class), ..
+        }) => Ok(Expression::Object(class.borrow().clone())),
+        _ => Ok(Expression::Void),
+    }
+}
+
+fn class_name(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("class-name", &args, 1)?;
+    Ok(Expression::String(object(&args[0])?.name.clone()))
+}
+
+fn parent_class(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("parent-class", &args, 1)?;
+    let obj = object(&args[0])?;
+    let parent = match &obj.class {
+        Some(class) => class.borrow().parent.clone(),
+        None => obj.parent.clone(),
+    };
+
+    Ok(parent
+        .map(|parent| Expression::Object(parent.borrow().clone()))
+        .unwrap_or(Expression::Void))
+}
+
+// values stored directly on the object which are not functions
+fn fields(eval: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("fields", &args, 1)?;
+    let mut names = eval
+        .object_env(object(&args[0])?)?
+        .borrow()
+        .record
+        .iter()
+        .filter(|(name, value)| {
+            !name.starts_with('#') && !matches!(value, Expression::Function(..))
+        })
+        .map(|(name, _)| name.clone())
+        .collect::<Vec<String>>();
+    names.sort();
+
+    Ok(Expression::List(
+        names.into_iter().map(Expression::String).collect(),
+    ))
+}
+
+// functions reachable through the class chain of the object
+fn methods(eval: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("methods", &args, 1)?;
+    let mut names = eval
+        .object_envs(object(&args[0])?)
+        .iter()
+        .flat_map(|env| {
+            env.borrow()
+                .record
+                .iter()
+                .filter(|(_, value)| matches!(value, Expression::Function(..)))
+                .map(|(name, _)| name.clone())
+                .collect::<Vec<String>>()
+        })
+        .collect::<Vec<String>>();
+    names.sort();
+    names.dedup();
+
+    Ok(Expression::List(
+        names.into_iter().map(Expression::String).collect(),
+    ))
+}
+
+fn has_prop(eval: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("has-prop?", &args, 2)?;
+    let name = string("has-prop?", &args[1])?;
+
+    Ok(Expression::Boolean(
+        eval.object_envs(object(&args[0])?)
+            .iter()
+            .any(|env| env.borrow().record.contains_key(name)),
+    ))
+}
@@ -0,0 +1,164 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    error::Error,
+    eval::Evaluator,
+    expression::{Expression, Regex},
+};
+
+use super::{expect_args, string, NativeFn};
+
+pub const FUNCTIONS: &[(&str, NativeFn)] = &[
+    ("compile", compile),
+    ("matches?", matches),
+    ("find", find),
+    ("find-all", find_all),
+    ("captures", captures),
+    ("named-captures", named_captures),
+    ("replace", replace),
+    ("replace-all", replace_all),
+    ("split", split),
+];
+
+fn compile_pattern(pattern: &str) -> Result<Regex, Error> {
+    ::regex::Regex::new(pattern)
+        .map(Regex)
+        .map_err(|err| Error::Invalid(format!("invalid regex {}: {}", pattern, err)))
+}
+
+// functions take a compiled regex or compile a pattern string on the fly
+fn regex_arg(name: &str, exp: &Expression) -> Result<Regex, Error> {
+    match exp {
+        Expression::Regex(re) => Ok(re.clone()),
+        Expression::String(pattern) => compile_pattern(pattern),
+        other => Err(Error::Invalid(format!(
+            "{} expects a regex or a pattern string, got {}",
+            name, other
+        ))),
+    }
+}
+
+fn group(capture: Option<::regex::Match>) -> Expression {
+    capture
+        .map(|capture| Expression::String(capture.as_str().to_string()))
+        .unwrap_or(Expression::Void)
+}
+
+// every group of a match by index, groups which did not participate are nil
+fn groups(captures: &::regex::Captures) -> Expression {
+    Expression::List(captures.iter().map(group).collect())
+}
+
+fn compile(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("compile", &args, 1)?;
+    Ok(Expression::Regex(compile_pattern(string(
+        "compile", &args[0],
+    )?)?))
+}
+
+fn matches(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("matches?", &args, 2)?;
+    let re = regex_arg("matches?", &args[0])?;
+    Ok(Expression::Boolean(
+        re.0.is_match(string("matches?", &args[1])?),
+    ))
+}
+
+// first match or nil
+fn find(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("find", &args, 2)?;
+    let re = regex_arg("find", &args[0])?;
+    Ok(group(re.0.find(string("find", &args[1])?)))
+}
+
+fn find_all(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("find-all", &args, 2)?;
+    let re = regex_arg("find-all", &args[0])?;
+    Ok(Expression::List(
+        re.0.find_iter(string("find-all", &args[1])?)
+            .map(|found| Expression::String(found.as_str().to_string()))
+            .collect(),
+    ))
+}
+
+// groups of the first match, index 0 is the whole match, nil without a match
+fn captures(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("captures", &args, 2)?;
+    let re = regex_arg("captures", &args[0])?;
+    Ok(re
+        .0
+        .captures(string("captures", &args[1])?)
+        .map(|captures| groups(&captures))
+        .unwrap_or(Expression::Void))
+}
+
+// map of the named groups of the first match, nil without a match
+fn named_captures(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("named-captures", &args, 2)?;
+    let re = regex_arg("named-captures", &args[0])?;
+    let Some(captures) = re.0.captures(string("named-captures", &args[1])?) else {
+        return Ok(Expression::Void);
+    };
+
+    Ok(Expression::Map(
+        re.0.capture_names()
+            .flatten()
+            .map(|name| (name.to_string(), group(captures.name(name))))
+            .collect::<BTreeMap<String, Expression>>(),
+    ))
+}
+
+// the replacement is a string where $1 or ${name} refer to groups, or a function
+// called with the list of groups of each match
+fn replace_matches(
+    eval: &mut Evaluator,
+    name: &str,
+    args: Vec<Expression>,
+    limit: usize,
+) -> Result<Expression, Error> {
+    expect_args(name, &args, 3)?;
+    let re = regex_arg(name, &args[0])?;
+    let text = string(name, &args[1])?;
+
+    if let Expression::String(replacement) = &args[2] {
+        return Ok(Expression::String(
+            re.0.replacen(text, limit, replacement.as_str()).to_string(),
+        ));
+    }
+
+    let mut res = String::new();
+    let mut last = 0;
+    for captures in re.0.captures_iter(text).take(match limit {
+        0 => usize::MAX,
+        limit => limit,
+    }) {
+        let whole = captures.get(0).unwrap();
+        res += &text[last..whole.start()];
+        match eval.call_function(&args[2], vec![groups(&captures)])? {
+            Expression::String(str) => res += &str,
+            other => res += &eval.stringify(&other)?,
+        }
+        last = whole.end();
+    }
+    res += &text[last..];
+
+    Ok(Expression::String(res))
+}
+
+fn replace(eval: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    replace_matches(eval, "replace", args, 1)
+}
+
+fn replace_all(eval: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    replace_matches(eval, "replace-all", args, 0)
+}
+
+fn split(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("split", &args, 2)?;
+    let re = regex_arg("split", &args[0])?;
+    Ok(Expression::List(
+        re.0.split(string("split", &args[1])?)
+            .map(|part| Expression::String(part.to_string()))
+            .collect(),
+    ))
+}
@@ -0,0 +1,180 @@
+use crate::{error::Error, eval::Evaluator, expression::Expression};
+
+use super::{expect_args, integer, list, string, NativeFn};
+
+pub const FUNCTIONS: &[(&str, NativeFn)] = &[
+    ("length", length),
+    ("upper", upper),
+    ("lower", lower),
+    ("trim", trim),
+    ("split", split),
+    ("join", join),
+    ("contains?", contains),
+    ("starts-with?", starts_with),
+    ("ends-with?", ends_with),
+    ("replace", replace),
+    ("substring", substring),
+    ("index-of", index_of),
+    ("repeat", repeat),
+    ("chars", chars),
+    ("to-string", to_string),
+    ("to-number", to_number),
+];
+
+fn length(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("length", &args, 1)?;
+    Ok(Expression::Number(
+        string("length", &args[0])?.chars().count() as f64,
+    ))
+}
+
+fn upper(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("upper", &args, 1)?;
+    Ok(Expression::String(
+        string("upper", &args[0])?.to_uppercase(),
+    ))
+}
+
+fn lower(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("lower", &args, 1)?;
+    Ok(Expression::String(
+        string("lower", &args[0])?.to_lowercase(),
+    ))
+}
+
+fn trim(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("trim", &args, 1)?;
+    Ok(Expression::String(
+        string("trim", &args[0])?.trim().to_string(),
+    ))
+}
+
+fn split(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("split", &args, 2)?;
+    let (str, separator) = (string("split", &args[0])?, string("split", &args[1])?);
+
+    let parts = match separator {
+        "" => str
+            .chars()
+            .map(|ch| ch.to_string())
+            .collect::<Vec<String>>(),
+        _ => str.split(separator).map(|part| part.to_string()).collect(),
+    };
+
+    Ok(Expression::List(
+        parts.into_iter().map(Expression::String).collect(),
+    ))
+}
+
+fn join(eval: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("join", &args, 2)?;
+    let separator = string("join", &args[1])?;
+
+    let parts = list("join", &args[0])?
+        .iter()
+        .map(|item| eval.stringify(item))
+        .collect::<Result<Vec<String>, Error>>()?;
+
+    Ok(Expression::String(parts.join(separator)))
+}
+
+fn contains(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("contains?", &args, 2)?;
+    Ok(Expression::Boolean(
+        string("contains?", &args[0])?.contains(string("contains?", &args[1])?),
+    ))
+}
+
+fn starts_with(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("starts-with?", &args, 2)?;
+    Ok(Expression::Boolean(
+        string("starts-with?", &args[0])?.starts_with(string("starts-with?", &args[1])?),
+    ))
+}
+
+fn ends_with(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("ends-with?", &args, 2)?;
+    Ok(Expression::Boolean(
+        string("ends-with?", &args[0])?.ends_with(string("ends-with?", &args[1])?),
+    ))
+}
+
+fn replace(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("replace", &args, 3)?;
+    Ok(Expression::String(string("replace", &args[0])?.replace(
+        string("replace", &args[1])?,
+        string("replace", &args[2])?,
+    )))
+}
+
+// (substring s start end) with character indexes, end is exclusive
+fn substring(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("substring", &args, 3)?;
+    let str = string("substring", &args[0])?;
+    let (start, end) = (
+        integer("substring", &args[1])?,
+        integer("substring", &args[2])?,
+    );
+
+    let count = str.chars().count() as i64;
+    if start < 0 || end < start || end > count {
+        return Err(Error::Reason(format!(
+            "substring range {}..{} out of bounds for string of length {}",
+            start, end, count
+        )));
+    }
+
+    Ok(Expression::String(
+        str.chars()
+            .skip(start as usize)
+            .take((end - start) as usize)
+            .collect(),
+    ))
+}
+
+// character index of the first occurrence, -1 when missing
+fn index_of(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("index-of", &args, 2)?;
+    let (str, pattern) = (string("index-of", &args[0])?, string("index-of", &args[1])?);
+
+    Ok(Expression::Number(match str.find(pattern) {
+        Some(byte_idx) => str[..byte_idx].chars().count() as f64,
+        None => -1.0,
+    }))
+}
+
+fn repeat(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("repeat", &args, 2)?;
+    let count = integer("repeat", &args[1])?;
+    if count < 0 {
+        return Err(Error::Reason("repeat count cannot be negative".to_string()));
+    }
+
+    Ok(Expression::String(
+        string("repeat", &args[0])?.repeat(count as usize),
+    ))
+}
+
+fn chars(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("chars", &args, 1)?;
+    Ok(Expression::List(
+        string("chars", &args[0])?
+            .chars()
+            .map(|ch| Expression::String(ch.to_string()))
+            .collect(),
+    ))
+}
+
+fn to_string(eval: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("to-string", &args, 1)?;
+    Ok(Expression::String(eval.stringify(&args[0])?))
+}
+
+// number parsed from a string, nil when it is not a number
+fn to_number(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("to-number", &args, 1)?;
+    Ok(match string("to-number", &args[0])?.trim().parse::<f64>() {
+        Ok(num) => Expression::Number(num),
+        Err(_) => Expression::Void,
+    })
+}
@@ -0,0 +1,94 @@
+use crate::{error::Error, eval::Evaluator, expression::Expression};
+
+use super::{format::repr_of, string, truthy, NativeFn};
+
+pub const FUNCTIONS: &[(&str, NativeFn)] = &[("assert", assert), ("assert-eq", assert_eq)];
+
+// (assert cond) or (assert cond message)
+fn assert(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    let (cond, message) = match args.as_slice() {
+        [cond] => (cond, "assertion failed"),
+        [cond, message] => (cond, string("assert", message)?),
+        _ => {
+            return Err(Error::Invalid(format!(
+                "assert expects 1 or 2 argument(s), got {}",
+                args.len()
+            )))
+        }
+    };
+
+    if !truthy(cond) {
+        return Err(Error::Assertion(message.to_string()));
+    }
+
+    Ok(Expression::Boolean(true))
+}
+
+// (assert-eq actual expected), the failure shows both values and a line diff for
+// multi-line strings
+fn assert_eq(eval: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    let [actual, expected] = args.as_slice() else {
+        return Err(Error::Invalid(format!(
+            "assert-eq expects 2 argument(s), got {}",
+            args.len()
+        )));
+    };
+
+    if actual == expected {
+        return Ok(Expression::Boolean(true));
+    }
+
+    let message = match (expected, actual) {
+        (Expression::String(expected), Expression::String(actual))
+            if expected.contains('\n') || actual.contains('\n') =>
+        {
+            format!(
+                "assert-eq failed, strings differ (- expected, + actual)\n{}",
+                diff_lines(expected, actual)
+            )
+        }
+        _ => format!(
+            "assert-eq failed\n  expected: {}\n  actual:   {}",
+            repr_of(eval, expected)?,
+            repr_of(eval, actual)?
+        ),
+    };
+
+    Err(Error::Assertion(message))
+}
+
+// lines of both texts aligned on their longest common subsequence
+fn diff_lines(expected: &str, actual: &str) -> String {
+    let expected = expected.lines().collect::<Vec<&str>>();
+    let actual = actual.lines().collect::<Vec<&str>>();
+
+    // common[i][j] is the length of the lcs of expected[i..] and actual[j..]
+    let mut common = vec![vec![0; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            common[i][j] = match expected[i] == actual[j] {
+                true => common[i + 1][j + 1] + 1,
+                false => common[i + 1][j].max(common[i][j + 1]),
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut out = vec![];
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            out.push(format!("    {}", expected[i]));
+            i += 1;
+            j += 1;
+        } else if j == actual.len() || (i < expected.len() && common[i + 1][j] >= common[i][j + 1])
+        {
+            out.push(format!("  - {}", expected[i]));
+            i += 1;
+        } else {
+            out.push(format!("  + {}", actual[j]));
+            j += 1;
+        }
+    }
+
+    out.join("\n")
+}
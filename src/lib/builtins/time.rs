@@ -0,0 +1,285 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::OnceLock,
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use chrono::{
+    format::{Item, StrftimeItems},
+    DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime, TimeDelta, TimeZone, Timelike, Utc,
+};
+
+use crate::{error::Error, eval::Evaluator, expression::Expression};
+
+use super::{expect_args, integer, number, string, NativeFn};
+
+pub const FUNCTIONS: &[(&str, NativeFn)] = &[
+    ("now", now),
+    ("monotonic", monotonic),
+    ("sleep", sleep),
+    ("datetime", datetime),
+    ("parse-datetime", parse_datetime),
+    ("format-datetime", format_datetime),
+    ("timestamp", timestamp),
+    ("from-timestamp", from_timestamp),
+    ("parts", parts),
+    ("with-offset", with_offset),
+    ("add", add),
+    ("diff", diff),
+];
+
+fn datetime_arg(name: &str, exp: &Expression) -> Result<DateTime<FixedOffset>, Error> {
+    match exp {
+        Expression::DateTime(dt) => Ok(*dt),
+        other => Err(Error::Invalid(format!(
+            "{} expects a datetime, got {}",
+            name, other
+        ))),
+    }
+}
+
+// durations are numbers of milliseconds, like now and sleep
+pub fn shift(dt: &DateTime<FixedOffset>, ms: f64) -> Result<DateTime<FixedOffset>, Error> {
+    let out_of_range = || Error::Invalid(format!("datetime out of range: {} + {} ms", dt, ms));
+    if !ms.is_finite() {
+        return Err(out_of_range());
+    }
+
+    dt.checked_add_signed(TimeDelta::microseconds((ms * 1000.0).round() as i64))
+        .ok_or_else(out_of_range)
+}
+
+pub fn between(left: &DateTime<FixedOffset>, right: &DateTime<FixedOffset>) -> f64 {
+    (*left - *right).num_microseconds().unwrap_or(i64::MAX) as f64 / 1000.0
+}
+
+// milliseconds since the unix epoch
+fn now(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("now", &args, 0)?;
+    let elapsed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|err| Error::Reason(err.to_string()))?;
+
+    Ok(Expression::Number(elapsed.as_millis() as f64))
+}
+
+// milliseconds since the first call, never goes backwards unlike now
+fn monotonic(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    static START: OnceLock<Instant> = OnceLock::new();
+
+    expect_args("monotonic", &args, 0)?;
+    let start = START.get_or_init(Instant::now);
+    Ok(Expression::Number(start.elapsed().as_secs_f64() * 1000.0))
+}
+
+// (sleep ms)
+fn sleep(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("sleep", &args, 1)?;
+    let ms = number("sleep", &args[0])?;
+    if !ms.is_finite() || ms < 0.0 {
+        return Err(Error::Invalid(format!("cannot sleep for {} ms", ms)));
+    }
+
+    thread::sleep(Duration::from_secs_f64(ms / 1000.0));
+    Ok(Expression::Void)
+}
+
+// (datetime) is the current time in utc, (datetime year month day [hour minute second ms])
+// builds a utc datetime
+fn datetime(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    if args.is_empty() {
+        return Ok(Expression::DateTime(Utc::now().fixed_offset()));
+    }
+    if !(3..=7).contains(&args.len()) {
+        return Err(Error::Invalid(format!(
+            "datetime expects 0 or 3 to 7 argument(s), got {}",
+            args.len()
+        )));
+    }
+
+    let mut fields = [0; 7];
+    for (idx, arg) in args.iter().enumerate() {
+        fields[idx] = integer("datetime", arg)?;
+    }
+    let [year, month, day, hour, minute, second, ms] = fields;
+
+    let invalid = || {
+        Error::Invalid(format!(
+            "invalid datetime {}-{:02}-{:02} {:02}:{:02}:{:02}.{:03}",
+            year, month, day, hour, minute, second, ms
+        ))
+    };
+    let field = |value: i64| u32::try_from(value).map_err(|_| invalid());
+
+    let date = NaiveDate::from_ymd_opt(
+        i32::try_from(year).map_err(|_| invalid())?,
+        field(month)?,
+        field(day)?,
+    )
+    .ok_or_else(invalid)?;
+    let time = date
+        .and_hms_milli_opt(field(hour)?, field(minute)?, field(second)?, field(ms)?)
+        .ok_or_else(invalid)?;
+
+    Ok(Expression::DateTime(time.and_utc().fixed_offset()))
+}
+
+// (parse-datetime text) reads iso 8601, (parse-datetime text format) a strftime format,
+// text without an offset is taken as utc and a date without a time as midnight
+fn parse_datetime(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    let (text, formats) = match args.as_slice() {
+        [text] => (
+            string("parse-datetime", text)?,
+            vec![
+                "%Y-%m-%dT%H:%M:%S%.f%:z",
+                "%Y-%m-%d %H:%M:%S%.f%:z",
+                "%Y-%m-%dT%H:%M:%S%.f",
+                "%Y-%m-%d %H:%M:%S%.f",
+                "%Y-%m-%dT%H:%M",
+                "%Y-%m-%d",
+            ],
+        ),
+        [text, format] => (
+            string("parse-datetime", text)?,
+            vec![string("parse-datetime", format)?],
+        ),
+        _ => {
+            return Err(Error::Invalid(format!(
+                "parse-datetime expects 1 or 2 argument(s), got {}",
+                args.len()
+            )))
+        }
+    };
+
+    // a Z suffix is the utc offset
+    let text = match text.strip_suffix(['Z', 'z']) {
+        Some(rest) if args.len() == 1 => format!("{}+00:00", rest),
+        _ => text.to_string(),
+    };
+
+    let mut last_err = None;
+    for format in formats {
+        let parsed = DateTime::parse_from_str(&text, format)
+            .or_else(|_| {
+                NaiveDateTime::parse_from_str(&text, format).map(|dt| dt.and_utc().fixed_offset())
+            })
+            .or_else(|_| {
+                NaiveDate::parse_from_str(&text, format)
+                    .map(|date| date.and_time(Default::default()).and_utc().fixed_offset())
+            });
+        match parsed {
+            Ok(dt) => return Ok(Expression::DateTime(dt)),
+            Err(err) => last_err = Some(err),
+        }
+    }
+
+    Err(Error::Invalid(format!(
+        "cannot parse datetime {}: {}",
+        text,
+        last_err.map(|err| err.to_string()).unwrap_or_default()
+    )))
+}
+
+// (format-datetime dt) writes iso 8601, (format-datetime dt format) a strftime format
+fn format_datetime(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    let (dt, format) = match args.as_slice() {
+        [dt] => (datetime_arg("format-datetime", dt)?, None),
+        [dt, format] => (
+            datetime_arg("format-datetime", dt)?,
+            Some(string("format-datetime", format)?),
+        ),
+        _ => {
+            return Err(Error::Invalid(format!(
+                "format-datetime expects 1 or 2 argument(s), got {}",
+                args.len()
+            )))
+        }
+    };
+
+    let Some(format) = format else {
+        return Ok(Expression::String(Expression::DateTime(dt).to_string()));
+    };
+
+    let invalid = || Error::Invalid(format!("invalid datetime format {}", format));
+    let items = StrftimeItems::new(format).collect::<Vec<Item>>();
+    if items.contains(&Item::Error) {
+        return Err(invalid());
+    }
+
+    let mut out = String::new();
+    write!(out, "{}", dt.format_with_items(items.iter())).map_err(|_| invalid())?;
+    Ok(Expression::String(out))
+}
+
+// milliseconds since the unix epoch
+fn timestamp(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("timestamp", &args, 1)?;
+    let dt = datetime_arg("timestamp", &args[0])?;
+    Ok(Expression::Number(dt.timestamp_millis() as f64))
+}
+
+fn from_timestamp(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("from-timestamp", &args, 1)?;
+    let ms = number("from-timestamp", &args[0])?;
+    let epoch = Utc.timestamp_opt(0, 0).unwrap().fixed_offset();
+    Ok(Expression::DateTime(shift(&epoch, ms)?))
+}
+
+// map of the fields of a datetime in its offset, weekday goes from 1 for monday to 7 and
+// offset is in minutes
+fn parts(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("parts", &args, 1)?;
+    let dt = datetime_arg("parts", &args[0])?;
+
+    Ok(Expression::Map(BTreeMap::from(
+        [
+            ("year", dt.year() as f64),
+            ("month", dt.month() as f64),
+            ("day", dt.day() as f64),
+            ("hour", dt.hour() as f64),
+            ("minute", dt.minute() as f64),
+            ("second", dt.second() as f64),
+            ("millisecond", dt.timestamp_subsec_millis() as f64),
+            ("weekday", dt.weekday().number_from_monday() as f64),
+            ("offset", (dt.offset().local_minus_utc() / 60) as f64),
+        ]
+        .map(|(name, value)| (name.to_string(), Expression::Number(value))),
+    )))
+}
+
+// (with-offset dt offset) is the same instant shown at an offset like "+02:00", "Z" or a
+// number of minutes
+fn with_offset(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("with-offset", &args, 2)?;
+    let dt = datetime_arg("with-offset", &args[0])?;
+
+    let offset = match &args[1] {
+        Expression::String(offset) if offset == "Z" => FixedOffset::east_opt(0),
+        Expression::String(offset) => offset.parse::<FixedOffset>().ok(),
+        Expression::Number(minutes) if minutes.fract() == 0.0 && minutes.abs() < 1440.0 => {
+            FixedOffset::east_opt(*minutes as i32 * 60)
+        }
+        _ => None,
+    }
+    .ok_or_else(|| Error::Invalid(format!("invalid utc offset {}", args[1])))?;
+
+    Ok(Expression::DateTime(dt.with_timezone(&offset)))
+}
+
+// (add dt ms)
+fn add(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("add", &args, 2)?;
+    let dt = datetime_arg("add", &args[0])?;
+    Ok(Expression::DateTime(shift(&dt, number("add", &args[1])?)?))
+}
+
+// (diff a b) is the number of milliseconds from b to a
+fn diff(_: &mut Evaluator, args: Vec<Expression>) -> Result<Expression, Error> {
+    expect_args("diff", &args, 2)?;
+    Ok(Expression::Number(between(
+        &datetime_arg("diff", &args[0])?,
+        &datetime_arg("diff", &args[1])?,
+    )))
+}
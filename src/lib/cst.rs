@@ -0,0 +1,386 @@
+use std::fmt::Display;
+
+use crate::{error::Error, expression::Expression, parser::parse};
+
+// byte range in the source text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriviaKind {
+    Whitespace, // spaces and tabs
+    Newline,    // \n or \r\n
+    Comment,    // from ; to the end of the line
+}
+
+// source text which is not part of a token
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trivia {
+    pub kind: TriviaKind,
+    pub text: String,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    LParen,
+    RParen,
+    Number,
+    String,
+    Interpolated,
+    Symbol,
+    Eof, // empty token holding the trivia at the end of the source
+}
+
+// token with the trivia around it, trailing trivia runs up to the end of the line the token
+// is on and leading trivia holds the rest
+#[derive(Debug, Clone, PartialEq)]
+pub struct CstToken {
+    pub kind: TokenKind,
+    pub text: String,
+    pub span: Span,
+    pub leading: Vec<Trivia>,
+    pub trailing: Vec<Trivia>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Node {
+    Atom(CstToken),
+    List {
+        open: CstToken,
+        items: Vec<Node>,
+        close: CstToken,
+    },
+}
+
+// lossless syntax tree, printing it gives back the source it was parsed from
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntaxTree {
+    pub items: Vec<Node>,
+    pub eof: CstToken,
+}
+
+pub fn parse_cst(source: &str) -> Result<SyntaxTree, Error> {
+    let mut tokens = Lexer::new(source).tokenize()?;
+    tokens.reverse();
+
+    let items = parse_nodes(&mut tokens, false)?;
+    match tokens.pop() {
+        Some(eof) if eof.kind == TokenKind::Eof => Ok(SyntaxTree { items, eof }),
+        _ => Err(Error::Parse("unexpected ')'".to_string())),
+    }
+}
+
+// nodes up to the closing paren of the current list, which is left in tokens
+fn parse_nodes(tokens: &mut Vec<CstToken>, nested: bool) -> Result<Vec<Node>, Error> {
+    let mut nodes = vec![];
+    while let Some(token) = tokens.pop() {
+        match token.kind {
+            TokenKind::LParen => {
+                let items = parse_nodes(tokens, true)?;
+                match tokens.pop() {
+                    Some(close) if close.kind == TokenKind::RParen => nodes.push(Node::List {
+                        open: token,
+                        items,
+                        close,
+                    }),
+                    _ => return Err(Error::Parse("could not find closing ')'".to_string())),
+                }
+            }
+            TokenKind::RParen if !nested => return Err(Error::Parse("unexpected ')'".to_string())),
+            TokenKind::RParen | TokenKind::Eof => {
+                tokens.push(token);
+                break;
+            }
+            _ => nodes.push(Node::Atom(token)),
+        }
+    }
+
+    Ok(nodes)
+}
+
+impl SyntaxTree {
+    // tokens in source order, ending with the eof token
+    pub fn tokens(&self) -> Vec<&CstToken> {
+        let mut tokens = vec![];
+        for item in &self.items {
+            item.collect_tokens(&mut tokens);
+        }
+        tokens.push(&self.eof);
+        tokens
+    }
+
+    // token whose text covers the byte offset
+    pub fn token_at(&self, offset: usize) -> Option<&CstToken> {
+        self.tokens()
+            .into_iter()
+            .find(|token| token.span.start <= offset && offset < token.span.end)
+    }
+
+    // the program as parser::parse reads it, a list of the top-level forms
+    pub fn to_expression(&self) -> Result<Expression, Error> {
+        Ok(Expression::List(
+            self.items
+                .iter()
+                .map(Node::to_expression)
+                .collect::<Result<Vec<Expression>, Error>>()?,
+        ))
+    }
+}
+
+impl Node {
+    // from the first to the last token of the node, without the trivia around it
+    pub fn span(&self) -> Span {
+        match self {
+            Node::Atom(token) => token.span,
+            Node::List { open, close, .. } => Span {
+                start: open.span.start,
+                end: close.span.end,
+            },
+        }
+    }
+
+    pub fn to_expression(&self) -> Result<Expression, Error> {
+        match self {
+            Node::List { items, .. } => Ok(Expression::List(
+                items
+                    .iter()
+                    .map(Node::to_expression)
+                    .collect::<Result<Vec<Expression>, Error>>()?,
+            )),
+            Node::Atom(token) => match token.kind {
+                TokenKind::Number => token
+                    .text
+                    .parse::<f64>()
+                    .map(Expression::Number)
+                    .map_err(|_| Error::Parse(format!("invalid number {}", token.text))),
+                TokenKind::String => Ok(Expression::String(
+                    token.text[1..token.text.len() - 1].to_string(),
+                )),
+                // the parser desugars f-strings into a format call
+                TokenKind::Interpolated => match parse(&token.text)? {
+                    Expression::List(mut forms) if forms.len() == 1 => Ok(forms.remove(0)),
+                    _ => Err(Error::Parse(format!(
+                        "invalid interpolated string {}",
+                        token.text
+                    ))),
+                },
+                _ => Ok(Expression::Symbol(token.text.clone())),
+            },
+        }
+    }
+
+    fn collect_tokens<'a>(&'a self, tokens: &mut Vec<&'a CstToken>) {
+        match self {
+            Node::Atom(token) => tokens.push(token),
+            Node::List { open, items, close } => {
+                tokens.push(open);
+                for item in items {
+                    item.collect_tokens(tokens);
+                }
+                tokens.push(close);
+            }
+        }
+    }
+}
+
+impl Display for CstToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for trivia in &self.leading {
+            write!(f, "{}", trivia.text)?;
+        }
+        write!(f, "{}", self.text)?;
+        for trivia in &self.trailing {
+            write!(f, "{}", trivia.text)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for SyntaxTree {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for token in self.tokens() {
+            write!(f, "{}", token)?;
+        }
+        Ok(())
+    }
+}
+
+// splits the source into the same tokens as lexer::tokenize, keeping everything else as trivia
+struct Lexer<'a> {
+    source: &'a str,
+    pos: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(source: &'a str) -> Self {
+        Lexer { source, pos: 0 }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.source[self.pos..].chars().next()
+    }
+
+    fn peek_second(&self) -> Option<char> {
+        self.source[self.pos..].chars().nth(1)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let ch = self.peek()?;
+        self.pos += ch.len_utf8();
+        Some(ch)
+    }
+
+    fn span_from(&self, start: usize) -> Span {
+        Span {
+            start,
+            end: self.pos,
+        }
+    }
+
+    fn tokenize(mut self) -> Result<Vec<CstToken>, Error> {
+        let mut tokens = vec![];
+        loop {
+            let leading = self.trivia(false);
+            let start = self.pos;
+            let kind = match self.bump() {
+                None => TokenKind::Eof,
+                Some('(') => TokenKind::LParen,
+                Some(')') => TokenKind::RParen,
+                Some('"') => {
+                    self.string()?;
+                    TokenKind::String
+                }
+                Some('f') if self.peek() == Some('"') => {
+                    self.bump();
+                    self.interpolated()?;
+                    TokenKind::Interpolated
+                }
+                Some(_) => {
+                    while self
+                        .peek()
+                        .is_some_and(|ch| !ch.is_whitespace() && !matches!(ch, '(' | ')' | ';'))
+                    {
+                        self.bump();
+                    }
+                    match self.source[start..self.pos].parse::<f64>() {
+                        Ok(_) => TokenKind::Number,
+                        Err(_) => TokenKind::Symbol,
+                    }
+                }
+            };
+
+            let text = self.source[start..self.pos].to_string();
+            let span = self.span_from(start);
+            let trailing = match kind {
+                TokenKind::Eof => vec![],
+                _ => self.trivia(true),
+            };
+            tokens.push(CstToken {
+                kind,
+                text,
+                span,
+                leading,
+                trailing,
+            });
+
+            if kind == TokenKind::Eof {
+                return Ok(tokens);
+            }
+        }
+    }
+
+    // whitespace, newlines and comments, trailing trivia stops before the first newline
+    fn trivia(&mut self, trailing: bool) -> Vec<Trivia> {
+        let mut trivia = vec![];
+        while let Some(ch) = self.peek() {
+            let start = self.pos;
+            let kind = match ch {
+                '\n' if trailing => break,
+                '\r' if trailing && self.peek_second() == Some('\n') => break,
+                '\n' => {
+                    self.bump();
+                    TriviaKind::Newline
+                }
+                '\r' if self.peek_second() == Some('\n') => {
+                    self.pos += 2;
+                    TriviaKind::Newline
+                }
+                ';' => {
+                    while self.peek().is_some_and(|ch| ch != '\n')
+                        && !self.source[self.pos..].starts_with("\r\n")
+                    {
+                        self.bump();
+                    }
+                    TriviaKind::Comment
+                }
+                ch if ch.is_whitespace() => {
+                    while self.peek().is_some_and(|ch| {
+                        ch.is_whitespace()
+                            && ch != '\n'
+                            && !self.source[self.pos..].starts_with("\r\n")
+                    }) {
+                        self.bump();
+                    }
+                    TriviaKind::Whitespace
+                }
+                _ => break,
+            };
+
+            trivia.push(Trivia {
+                kind,
+                text: self.source[start..self.pos].to_string(),
+                span: self.span_from(start),
+            });
+        }
+
+        trivia
+    }
+
+    // rest of a string literal after its opening quote, strings have no escapes
+    fn string(&mut self) -> Result<(), Error> {
+        loop {
+            match self.bump() {
+                Some('"') => return Ok(()),
+                Some(_) => (),
+                None => return Err(Error::Token("suspended string definition".to_string())),
+            }
+        }
+    }
+
+    // rest of an f-string after f", placeholders end at a } outside of parens and strings
+    // and everything after a : at their top level is the format spec
+    fn interpolated(&mut self) -> Result<(), Error> {
+        let unterminated = || Error::Token("suspended string definition".to_string());
+        loop {
+            match self.bump().ok_or_else(unterminated)? {
+                '"' => return Ok(()),
+                '{' if self.peek() == Some('{') => {
+                    self.bump();
+                }
+                '{' => {
+                    let (mut depth, mut in_string, mut in_spec) = (0, false, false);
+                    loop {
+                        let ch = self.bump().ok_or_else(|| {
+                            Error::Token("'}' closing interpolated expression".to_string())
+                        })?;
+                        match ch {
+                            '}' if in_spec || (!in_string && depth == 0) => break,
+                            _ if in_spec => (),
+                            '"' => in_string = !in_string,
+                            _ if in_string => (),
+                            '(' => depth += 1,
+                            ')' => depth -= 1,
+                            ':' if depth == 0 => in_spec = true,
+                            _ => (),
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+}
@@ -66,6 +66,14 @@ impl Environment {
         }
     }
 
+    // outermost environment of the chain
+    pub fn global(env: &Rc<RefCell<Environment>>) -> Rc<RefCell<Environment>> {
+        match &env.borrow().parent {
+            Some(parent) => Environment::global(parent),
+            None => env.clone(),
+        }
+    }
+
     // // implement identifier resolution
     fn resolve(&self, name: &str) -> Result<Rc<RefCell<Self>>, Error> {
         if let Some(parent_env) = &self.parent {
@@ -7,11 +7,58 @@ pub enum Error {
     Reference(String),
     Token(String),
     Parse(String),
+    Import(String),
+    Io(String),
+    Thrown(String),    // raised by throw
+    Assertion(String), // raised by a failing assert
+    Exit(i32),         // raised by exit, never caught by try
 }
 
 impl From<std::io::Error> for Error {
     fn from(value: std::io::Error) -> Self {
-        Error::Reason(value.to_string())
+        Error::Io(value.to_string())
+    }
+}
+
+impl Error {
+    // kind of the error as seen by catch
+    pub fn kind(&self) -> &'static str {
+        use Error::*;
+        match self {
+            Invalid(_) => "invalid",
+            Reason(_) | Thrown(_) => "error",
+            Assertion(_) => "assertion",
+            Reference(_) => "reference",
+            Token(_) => "token",
+            Parse(_) => "parse",
+            Import(_) => "import",
+            Io(_) => "io",
+            Exit(_) => "exit",
+        }
+    }
+
+    // error of a kind returned by kind(), used to raise a caught error again
+    pub fn from_kind(kind: &str, message: String) -> Error {
+        use Error::*;
+        match kind {
+            "invalid" => Invalid(message),
+            "assertion" => Assertion(message),
+            "reference" => Reference(message),
+            "token" => Token(message),
+            "parse" => Parse(message),
+            "import" => Import(message),
+            "io" => Io(message),
+            _ => Thrown(message),
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        use Error::*;
+        match self {
+            Invalid(msg) | Reason(msg) | Reference(msg) | Token(msg) | Parse(msg) | Import(msg)
+            | Io(msg) | Thrown(msg) | Assertion(msg) => msg,
+            Exit(_) => "",
+        }
     }
 }
 
@@ -24,6 +71,11 @@ impl Display for Error {
             Reason(err) => err.to_string(),
             Reference(err) => format!("reference error: {}", err),
             Token(err) => format!("missing token: {}", err),
+            Import(err) => format!("import error: {}", err),
+            Io(err) => format!("io error: {}", err),
+            Thrown(err) => format!("error: {}", err),
+            Assertion(err) => err.to_string(),
+            Exit(code) => format!("exit with status {}", code),
         };
         write!(f, "{}", str)
     }
@@ -1,20 +1,61 @@
-use std::{cell::RefCell, cmp::Ordering, fs, rc::Rc};
+use std::{
+    cell::RefCell,
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap, HashSet},
+    env, fs,
+    io::BufRead,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
 
 use crate::{
+    builtins::{self, format, input::Input, random::Rng},
     environment::Environment,
     error::Error,
-    expression::{Expression, Object},
+    expression::{Expression, Object, Record},
     parser::parse,
+    stdlib,
     transform::{
         transform_compound_assign, transform_def_to_var_lambda, transform_for_to_while,
-        transform_incdec, transform_module_to_class, transform_switch_to_if,
+        transform_incdec, transform_module_to_class, transform_program_to_block,
+        transform_switch_to_if,
     },
 };
 
 #[derive(Default, Debug)]
 pub struct Evaluator {
     cwf_path: String,
+    search_paths: Vec<String>,
     env_arena: Vec<Rc<RefCell<Environment>>>,
+    classes: HashMap<usize, ClassInfo>, // keyed by class environment index
+    modules: HashMap<PathBuf, Expression>, // imported modules keyed by canonical path
+    import_stack: Vec<(PathBuf, String)>, // modules being imported, canonical and resolved path
+    rng: Rng,                           // state of std.random
+    argv: Vec<String>,                  // arguments given to the script
+    input: Input,                       // source of read-line, stdin by default
+    tests: Vec<TestCase>,               // tests declared by deftest in the evaluated program
+    native_env: Option<Rc<RefCell<Environment>>>, // environment the running native was called from
+}
+
+// test declared by deftest, its body runs in a fresh scope of the environment declaring it
+#[derive(Debug)]
+pub struct TestCase {
+    pub name: String,
+    body: Expression,
+    env: Rc<RefCell<Environment>>,
+}
+
+// members declared by a class body which are not plain environment records
+#[derive(Default, Debug)]
+struct ClassInfo {
+    statics: HashSet<String>,
+    getters: HashMap<String, Expression>,
+    setters: HashMap<String, Expression>,
+    privates: HashSet<String>,
+    protocol: bool,
+    required: Vec<String>,        // methods required by a protocol
+    protocols: Vec<usize>,        // protocols implemented by a class
+    exports: Option<Vec<String>>, // names visible outside a module, none exports everything
 }
 
 impl Evaluator {
@@ -22,6 +63,79 @@ impl Evaluator {
         self.cwf_path = cwf_path;
     }
 
+    // extra directory searched by import after the importing file directory
+    pub fn add_search_path(&mut self, path: String) {
+        self.search_paths.push(path);
+    }
+
+    // makes std.random reproducible, the same seed always gives the same numbers
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = Rng::new(seed);
+    }
+
+    pub(crate) fn rng(&mut self) -> &mut Rng {
+        &mut self.rng
+    }
+
+    // command line arguments after the script name, exposed as argv
+    pub fn set_argv(&mut self, argv: Vec<String>) {
+        self.argv = argv;
+    }
+
+    // read-line, read-all and each-input-line read from this source instead of stdin
+    pub fn set_input(&mut self, source: impl BufRead + 'static) {
+        self.input = Input::new(source);
+    }
+
+    pub(crate) fn input(&mut self) -> &mut Input {
+        &mut self.input
+    }
+
+    // environment of the innermost native call, natives like eval work on it
+    pub(crate) fn caller_env(&self) -> Result<Rc<RefCell<Environment>>, Error> {
+        self.native_env
+            .clone()
+            .ok_or(Error::Reason("no environment to evaluate in".to_string()))
+    }
+
+    // tests declared so far, the registry is left empty
+    pub fn take_tests(&mut self) -> Vec<TestCase> {
+        std::mem::take(&mut self.tests)
+    }
+
+    pub fn run_test(&mut self, test: &TestCase) -> Result<(), Error> {
+        let mut env = Rc::new(RefCell::new(Environment::extend(test.env.clone())));
+        self.eval_exp(&test.body, &mut env).map(|_| ())
+    }
+
+    fn lookup_builtin(&self, name: &str) -> Option<Expression> {
+        match name {
+            "argv" => Some(Expression::List(
+                self.argv.iter().cloned().map(Expression::String).collect(),
+            )),
+            _ => builtins::global(name).map(|_| Expression::Native(name.to_string())),
+        }
+    }
+
+    // top-level forms of a parsed program, evaluated in env itself rather than in a nested
+    // block so that their definitions are global
+    pub fn eval_program(
+        &mut self,
+        program: &Expression,
+        env: &mut Rc<RefCell<Environment>>,
+    ) -> Result<Expression, Error> {
+        let Expression::List(forms) = program else {
+            return Err(Error::Invalid("invalid program".to_string()));
+        };
+
+        let mut result = Expression::Void;
+        for form in forms {
+            result = self.eval_exp(form, env)?;
+        }
+
+        Ok(result)
+    }
+
     pub fn eval_exp(
         &mut self,
         exp: &Expression,
@@ -34,8 +148,14 @@ impl Evaluator {
 
             Expression::String(str) => Ok(Expression::String(str.to_owned())),
 
-            // access variable
-            Expression::Symbol(str) => env.borrow_mut().lookup(str),
+            // access variable, names the program does not define fall back to the global built-ins
+            Expression::Symbol(str) => {
+                let value = env.borrow_mut().lookup(str);
+                match value {
+                    Err(err) => self.lookup_builtin(str).ok_or(err),
+                    value => value,
+                }
+            }
 
             Expression::List(list) => self.eval_list(list, env),
 
@@ -47,17 +167,25 @@ impl Evaluator {
                 *env_idx,
             )),
             Expression::Object(obj) => Ok(Expression::Object(obj.clone())),
+
+            Expression::Map(map) => Ok(Expression::Map(map.clone())),
+
+            Expression::Native(name) => Ok(Expression::Native(name.clone())),
+
+            Expression::Regex(re) => Ok(Expression::Regex(re.clone())),
+
+            Expression::DateTime(dt) => Ok(Expression::DateTime(*dt)),
         }
     }
 
     fn eval_list(
         &mut self,
-        list: &Vec<Expression>,
+        list: &[Expression],
         env: &mut Rc<RefCell<Environment>>,
     ) -> Result<Expression, Error> {
         use Expression::*;
 
-        if let Some(head) = list.get(0) {
+        if let Some(head) = list.first() {
             match head {
                 Symbol(s) => match s.as_str() {
                     "+" | "-" | "*" | "/" | "%" | "<" | "<=" | ">" | ">=" | "=" | "!=" | "&"
@@ -71,6 +199,10 @@ impl Evaluator {
                     "var" => self.eval_define_variable(list, env),
                     "set" => self.eval_assign_variable(list, env),
                     "if" => self.eval_if(list, env),
+                    "try" => self.eval_try(list, env),
+                    "throw" => self.eval_throw(list, env),
+                    "deftest" => self.eval_deftest(list, env),
+                    "assert-throws" => self.eval_assert_throws(list, env),
                     "switch" => self.eval_exp(&transform_switch_to_if(list)?, env),
                     "while" => self.eval_while(list, env),
                     "for" => self.eval_exp(&transform_for_to_while(list)?, env),
@@ -84,14 +216,25 @@ impl Evaluator {
                     "lambda" => self.eval_define_lambda(list, env),
                     "class" => self.eval_define_class(list, env),
                     "new" => self.eval_new(list, env),
+                    "static" => self.eval_static(list, env),
+                    // get only declares an accessor directly in a class body, elsewhere it is
+                    // an ordinary name
+                    "get" if self.class_of_env(env).is_some() => {
+                        self.eval_define_accessor(list, env)
+                    }
                     "prop" => self.eval_prop(list, env),
+                    "." => self.eval_method_call(list, env),
                     "super" => self.eval_super(list, env),
-                    "module" => self.eval_module(list, env),
+                    "module" | "mixin" => self.eval_module(list, env),
+                    "protocol" => self.eval_define_protocol(list, env),
+                    "implements?" => self.eval_implements(list, env),
                     "import" => self.eval_import(list, env),
+                    "export" => self.eval_export(list, env),
                     "print" => self.eval_print(list, env),
+                    "quote" => self.eval_quote(list),
                     // user defined functions or variables
-                    _ => {
-                        if let Ok(Function(params, body, env_idx)) = self.eval_exp(head, env) {
+                    _ => match self.eval_exp(head, env)? {
+                        Function(params, body, env_idx) => {
                             // static scope
                             let mut activation_env = Rc::new(RefCell::new(Environment::extend(
                                 self.env_arena
@@ -100,17 +243,14 @@ impl Evaluator {
                                     .clone(),
                             )));
 
-                            return self.eval_function_body(
-                                list,
-                                params,
-                                body,
-                                env,
-                                &mut activation_env,
-                            );
+                            self.eval_function_body(list, params, body, env, &mut activation_env)
                         }
-
-                        self.eval_exp(head, env)
-                    }
+                        Object(obj) if self.find_method(&obj, "__call__").is_some() => {
+                            self.eval_call_object(obj, &list[1..], env)
+                        }
+                        Native(name) => self.eval_call_native(&name, &list[1..], env),
+                        value => Ok(value),
+                    },
                 },
                 // immediately call function
                 _ => {
@@ -136,6 +276,13 @@ impl Evaluator {
                                 other => Err(other),
                             },
                         }
+                    } else if let Expression::Object(obj) = head_evaluated.clone() {
+                        match self.find_method(&obj, "__call__") {
+                            Some(_) => self.eval_call_object(obj, &list[1..], env),
+                            None => Ok(head_evaluated),
+                        }
+                    } else if let Expression::Native(name) = &head_evaluated {
+                        self.eval_call_native(name, &list[1..], env)
                     } else {
                         Ok(head_evaluated)
                     }
@@ -146,94 +293,316 @@ impl Evaluator {
         }
     }
 
+    // (import Mod), (import Mod as M), (import name Mod), (import (a (b as c) d as e) Mod)
+    // or (import * Mod)
     fn eval_import(
         &mut self,
         list: &[Expression],
         env: &mut Rc<RefCell<Environment>>,
     ) -> Result<Expression, Error> {
-        if let Some((module_name, rest)) = list.split_last() {
-            let module_file_path = match self.cwf_path.len() {
-                0 => format!("./{}.ema", module_name),
-                _ => format!("{}/{}.ema", self.cwf_path, module_name),
-            };
-
-            let ctx = fs::read_to_string(module_file_path)?;
+        let (module_path, alias, import_names) = match &list[1..] {
+            [Expression::Symbol(module_path)] => (module_path, None, None),
+            [Expression::Symbol(module_path), Expression::Symbol(tag), Expression::Symbol(alias)]
+                if tag == "as" =>
+            {
+                (module_path, Some(alias.clone()), None)
+            }
+            [import_names, Expression::Symbol(module_path)] => {
+                (module_path, None, Some(import_names))
+            }
+            _ => return Err(Error::Reason("invalid import".to_string())),
+        };
 
-            let body = if let Expression::List(body) = parse(&ctx)? {
-                if body.len() > 1 {
-                    return Err(Error::Reason("a module only contains one body".to_string()));
+        // utils.strings and utils/strings are bound as strings
+        let module_name = alias.unwrap_or(
+            module_path
+                .rsplit(['/', '.'])
+                .next()
+                .unwrap_or(module_path)
+                .to_string(),
+        );
+
+        // std.name is always the bundled module, a user file shadows it only through ./std/name
+        let module = match module_path
+            .strip_prefix("std.")
+            .or(module_path.strip_prefix("std/"))
+        {
+            Some(std_name) => self.import_std(std_name, &module_name, env)?,
+            None => {
+                let module_file_path = self.resolve_module(module_path)?;
+
+                let canonical_path = fs::canonicalize(&module_file_path)?;
+                match self.modules.get(&canonical_path) {
+                    Some(module) => module.clone(),
+                    None => {
+                        let ctx = fs::read_to_string(&module_file_path)?;
+                        self.load_module(
+                            &Expression::Symbol(module_name.clone()),
+                            transform_program_to_block(&parse(&ctx)?)?,
+                            &module_file_path,
+                            canonical_path,
+                            env,
+                        )?
+                    }
                 }
+            }
+        };
+        env.borrow_mut().define(&module_name, module.clone())?;
 
-                body[0].clone()
-            } else {
-                unreachable!()
-            };
+        let Some(import_names) = import_names else {
+            return Ok(module);
+        };
 
-            match rest.len() {
-                1 => self.eval_module(
-                    &[
-                        Expression::Symbol("module".to_string()),
-                        module_name.clone(),
-                        body,
-                    ],
-                    env,
-                ),
-                2 => {
-                    let [_tag, import_names] = rest else {
-                        unreachable!()
-                    };
+        let Expression::Object(obj) = &module else {
+            unreachable!()
+        };
 
-                    self.eval_module(
-                        &[
-                            Expression::Symbol("module".to_string()),
-                            module_name.clone(),
-                            body,
-                        ],
-                        env,
-                    )?;
-
-                    match import_names {
-                        Expression::List(names) => {
-                            let mut res = Expression::Void;
-                            for name in names {
-                                res = self.eval_exp(
-                                    &Expression::List(vec![
-                                        Expression::Symbol("var".to_string()),
-                                        name.clone(),
-                                        Expression::List(vec![
-                                            Expression::Symbol("prop".to_string()),
-                                            module_name.clone(),
-                                            name.clone(),
-                                        ]),
-                                    ]),
-                                    env,
-                                )?;
-                            }
-
-                            Ok(res)
-                        }
+        let names = match import_names {
+            Expression::Symbol(wildcard) if wildcard == "*" => self
+                .module_exports(obj)
+                .into_iter()
+                .map(|name| (name.clone(), name))
+                .collect(),
+            Expression::Symbol(name) => vec![(name.clone(), name.clone())],
+            Expression::List(names) => parse_import_names(names)?,
+            _ => return Err(Error::Reason("invalid import".to_string())),
+        };
 
-                        name => self.eval_exp(
-                            &Expression::List(vec![
-                                Expression::Symbol("var".to_string()),
-                                name.clone(),
-                                Expression::List(vec![
-                                    Expression::Symbol("prop".to_string()),
-                                    module_name.clone(),
-                                    name.clone(),
-                                ]),
-                            ]),
-                            env,
-                        ),
-                    }
-                }
-                _ => Err(Error::Reason("invalid import".to_string())),
+        let mut res = Expression::Void;
+        for (name, alias) in names {
+            if !self.is_exported(obj, &name) {
+                return Err(Error::Import(format!(
+                    "{} is not exported by module {}",
+                    name, module_path
+                )));
+            }
+
+            let value = self.lookup_prop(obj, &name)?;
+            res = env.borrow_mut().define(&alias, value)?;
+        }
+
+        Ok(res)
+    }
+
+    fn is_exported(&self, obj: &Object, name: &str) -> bool {
+        match self
+            .classes
+            .get(&obj.idx)
+            .and_then(|info| info.exports.as_ref())
+        {
+            Some(exports) => exports.iter().any(|export| export == name),
+            None => true,
+        }
+    }
+
+    // exported names of a module, or every member when it has no export declaration
+    fn module_exports(&self, obj: &Object) -> Vec<String> {
+        match self
+            .classes
+            .get(&obj.idx)
+            .and_then(|info| info.exports.clone())
+        {
+            Some(exports) => exports,
+            None => {
+                let mut names = self.env_arena[obj.idx]
+                    .borrow()
+                    .record
+                    .keys()
+                    .cloned()
+                    .collect::<Vec<String>>();
+                names.sort();
+                names
             }
-        } else {
-            Err(Error::Reason("invalid import".to_string()))
         }
     }
 
+    // (export name...) inside a module body restricts what other files can access
+    fn eval_export(
+        &mut self,
+        list: &[Expression],
+        env: &mut Rc<RefCell<Environment>>,
+    ) -> Result<Expression, Error> {
+        let class_idx = self.class_of_env(env).ok_or(Error::Invalid(
+            "export can only be used in a module body".to_string(),
+        ))?;
+
+        let names = list[1..]
+            .iter()
+            .map(|name| match name {
+                Expression::Symbol(name) => Ok(name.clone()),
+                _ => Err(Error::Invalid("invalid export name".to_string())),
+            })
+            .collect::<Result<Vec<String>, Error>>()?;
+
+        let exports = self
+            .classes
+            .get_mut(&class_idx)
+            .unwrap()
+            .exports
+            .get_or_insert(vec![]);
+        for name in names {
+            if !exports.contains(&name) {
+                exports.push(name);
+            }
+        }
+
+        Ok(Expression::Void)
+    }
+
+    // members of a module are only reachable from outside when exported
+    fn check_export(
+        &self,
+        obj: &Object,
+        name: &str,
+        env: &Rc<RefCell<Environment>>,
+    ) -> Result<(), Error> {
+        if obj.class.is_some()
+            || self.is_exported(obj, name)
+            || self.enclosing_class(env) == Some(obj.idx)
+        {
+            return Ok(());
+        }
+
+        Err(Error::Reference(format!(
+            "{} is not exported by module {}",
+            name, obj.name
+        )))
+    }
+
+    // bundled std modules bind their natives before evaluating the embedded ema source
+    fn import_std(
+        &mut self,
+        name: &str,
+        module_name: &str,
+        env: &Rc<RefCell<Environment>>,
+    ) -> Result<Expression, Error> {
+        let key = Path::new("std").join(name);
+        if let Some(module) = self.modules.get(&key) {
+            return Ok(module.clone());
+        }
+
+        let source = stdlib::source(name).ok_or(Error::Reference(format!(
+            "cannot find module std.{} in the standard library",
+            name
+        )))?;
+
+        let mut body = vec![Expression::Symbol("begin".to_string())];
+        for (constant, value) in builtins::constants(name) {
+            body.push(Expression::List(vec![
+                Expression::Symbol("var".to_string()),
+                Expression::Symbol(constant.to_string()),
+                Expression::Number(*value),
+            ]));
+        }
+        for (func, _) in builtins::functions(name) {
+            body.push(Expression::List(vec![
+                Expression::Symbol("var".to_string()),
+                Expression::Symbol(func.to_string()),
+                Expression::Native(format!("{}.{}", name, func)),
+            ]));
+        }
+        if let Expression::List(forms) = parse(source)? {
+            body.extend(forms);
+        }
+
+        self.load_module(
+            &Expression::Symbol(module_name.to_string()),
+            Expression::List(body),
+            &key,
+            key.clone(),
+            env,
+        )
+    }
+
+    // evaluate a module body once, modules are defined on top of the global environment
+    // so every importer shares the same state
+    fn load_module(
+        &mut self,
+        module_name: &Expression,
+        body: Expression,
+        module_file_path: &Path,
+        canonical_path: PathBuf,
+        env: &Rc<RefCell<Environment>>,
+    ) -> Result<Expression, Error> {
+        if let Some(start) = self
+            .import_stack
+            .iter()
+            .position(|(path, _)| path == &canonical_path)
+        {
+            let chain = self.import_stack[start..]
+                .iter()
+                .map(|(_, display)| display.clone())
+                .chain([module_file_path.display().to_string()])
+                .collect::<Vec<String>>();
+
+            return Err(Error::Import(format!(
+                "cyclic import {}",
+                chain.join(" -> ")
+            )));
+        }
+
+        // imports inside the module are resolved against its own directory
+        let importing_path = std::mem::replace(
+            &mut self.cwf_path,
+            module_file_path
+                .parent()
+                .map(|dir| dir.display().to_string())
+                .unwrap_or_default(),
+        );
+        self.import_stack.push((
+            canonical_path.clone(),
+            module_file_path.display().to_string(),
+        ));
+
+        let mut module_scope = Rc::new(RefCell::new(Environment::extend(Environment::global(env))));
+        let module = self.eval_module(
+            &[
+                Expression::Symbol("module".to_string()),
+                module_name.clone(),
+                body,
+            ],
+            &mut module_scope,
+        );
+
+        self.import_stack.pop();
+        self.cwf_path = importing_path;
+
+        let module = module?;
+        self.modules.insert(canonical_path, module.clone());
+        Ok(module)
+    }
+
+    // ./name and ../name are relative to the importing file only, other names are looked up
+    // in the importing file directory, the evaluator search paths and then EMA_PATH
+    fn resolve_module(&self, module_path: &str) -> Result<PathBuf, Error> {
+        let importing_dir = match self.cwf_path.len() {
+            0 => ".".to_string(),
+            _ => self.cwf_path.clone(),
+        };
+
+        let (relative, roots) = if module_path.starts_with("./") || module_path.starts_with("../") {
+            (format!("{}.ema", module_path), vec![importing_dir])
+        } else {
+            let mut roots = vec![importing_dir];
+            roots.extend(self.search_paths.iter().cloned());
+            if let Some(paths) = env::var_os("EMA_PATH") {
+                roots.extend(env::split_paths(&paths).map(|path| path.display().to_string()));
+            }
+
+            (format!("{}.ema", module_path.replace('.', "/")), roots)
+        };
+
+        roots
+            .iter()
+            .map(|root| Path::new(root).join(&relative))
+            .find(|path| path.is_file())
+            .ok_or(Error::Reference(format!(
+                "cannot find module {} in {}",
+                module_path,
+                roots.join(", ")
+            )))
+    }
+
     fn eval_module(
         &mut self,
         list: &[Expression],
@@ -247,7 +616,7 @@ impl Evaluator {
         list: &[Expression],
         env: &mut Rc<RefCell<Environment>>,
     ) -> Result<Expression, Error> {
-        let [_tag, name, parent, body] = &list else {
+        let [_tag, name, parent, clauses @ .., body] = &list else {
             return Err(Error::Invalid("invalid class/module definition".to_string()))
         };
 
@@ -256,6 +625,45 @@ impl Evaluator {
             _ => return Err(Error::Invalid("invalid class/module name".to_string())),
         };
 
+        // (implements Protocol...) and (with Mixin...) clauses between parent and body
+        let mut protocols = vec![];
+        let mut mixins = vec![];
+        for clause in clauses {
+            let Expression::List(clause) = clause else {
+                return Err(Error::Invalid(format!("invalid clause for class {}", name)));
+            };
+
+            let (target, items) = match clause.split_first() {
+                Some((Expression::Symbol(tag), items)) if tag == "implements" => {
+                    (&mut protocols, items)
+                }
+                Some((Expression::Symbol(tag), items)) if tag == "with" => (&mut mixins, items),
+                _ => return Err(Error::Invalid(format!("invalid clause for class {}", name))),
+            };
+
+            for item in items {
+                match self.eval_exp(item, env)? {
+                    Expression::Object(obj) if obj.class.is_none() => target.push(obj),
+                    other => {
+                        return Err(Error::Invalid(format!(
+                            "{} cannot be used in definition of class {}",
+                            other, name
+                        )))
+                    }
+                }
+            }
+        }
+
+        if let Some(obj) = protocols.iter().find(|obj| !self.is_protocol(obj.idx)) {
+            return Err(Error::Invalid(format!("{} is not a protocol", obj.name)));
+        }
+        if let Some(obj) = mixins.iter().find(|obj| self.is_protocol(obj.idx)) {
+            return Err(Error::Invalid(format!(
+                "protocol {} cannot be used as mixin",
+                obj.name
+            )));
+        }
+
         let mut parent_idx: Option<_> = None; // parent class
 
         // parent environment
@@ -279,6 +687,31 @@ impl Evaluator {
 
         let mut class_env = Rc::new(RefCell::new(Environment::extend(parent_env.clone())));
 
+        self.env_arena.push(class_env.clone());
+        let class_idx = self.env_arena.len() - 1;
+        self.classes.insert(class_idx, ClassInfo::default());
+
+        // mixin members are copied before the body so the class can override them,
+        // the leftmost mixin wins: class, mixins from left to right, then parent classes
+        for mixin in mixins.iter().rev() {
+            let record = self.env_arena[mixin.idx].borrow().record.clone();
+            for (name, value) in record {
+                class_env.borrow_mut().define(&name, value)?;
+            }
+
+            if let Some(mixin_info) = self.classes.get(&mixin.idx) {
+                let (statics, getters, setters) = (
+                    mixin_info.statics.clone(),
+                    mixin_info.getters.clone(),
+                    mixin_info.setters.clone(),
+                );
+                let info = self.classes.get_mut(&class_idx).unwrap();
+                info.statics.extend(statics);
+                info.getters.extend(getters);
+                info.setters.extend(setters);
+            }
+        }
+
         if let Expression::List(body_list) = body {
             match &body_list[0] {
                 Expression::Symbol(sym) if sym == &"begin".to_string() => {
@@ -293,17 +726,132 @@ impl Evaluator {
             self.eval_exp(body, &mut class_env)?;
         }
 
-        self.env_arena.push(class_env.clone());
+        // fields declared with a leading # are private to this class
+        let privates = class_env
+            .borrow()
+            .record
+            .keys()
+            .filter(|name| name.starts_with('#'))
+            .cloned()
+            .collect::<Vec<String>>();
+        if let Some(info) = self.classes.get_mut(&class_idx) {
+            info.privates.extend(privates);
+        }
+
+        let class = Object {
+            idx: class_idx,
+            name: name.clone(),
+            parent: parent_idx,
+            class: None,
+            record: None,
+        };
+
+        for protocol in &protocols {
+            let envs = self.object_envs(&class);
+            let missing = self.classes[&protocol.idx]
+                .required
+                .iter()
+                .filter(|method| {
+                    !envs.iter().any(|env| {
+                        matches!(
+                            env.borrow().record.get(*method),
+                            Some(Expression::Function(..))
+                        )
+                    })
+                })
+                .cloned()
+                .collect::<Vec<String>>();
+
+            if !missing.is_empty() {
+                return Err(Error::Invalid(format!(
+                    "class {} does not implement protocol {}, missing methods: {}",
+                    name,
+                    protocol.name,
+                    missing.join(", ")
+                )));
+            }
+        }
+
+        if let Some(info) = self.classes.get_mut(&class_idx) {
+            info.protocols = protocols.iter().map(|protocol| protocol.idx).collect();
+        }
+
+        env.borrow_mut().define(&name, Expression::Object(class))
+    }
+
+    fn is_protocol(&self, idx: usize) -> bool {
+        self.classes.get(&idx).is_some_and(|info| info.protocol)
+    }
+
+    // (protocol Name (method...))
+    fn eval_define_protocol(
+        &mut self,
+        list: &[Expression],
+        env: &mut Rc<RefCell<Environment>>,
+    ) -> Result<Expression, Error> {
+        let [_tag, Expression::Symbol(name), Expression::List(methods)] = list else {
+            return Err(Error::Invalid("invalid protocol definition".to_string()));
+        };
+
+        let required = methods
+            .iter()
+            .map(|method| match method {
+                Expression::Symbol(method) => Ok(method.clone()),
+                _ => Err(Error::Invalid(format!(
+                    "invalid method name in protocol {}",
+                    name
+                ))),
+            })
+            .collect::<Result<Vec<String>, Error>>()?;
+
+        self.env_arena
+            .push(Rc::new(RefCell::new(Environment::extend(env.clone()))));
+        let idx = self.env_arena.len() - 1;
+        self.classes.insert(
+            idx,
+            ClassInfo {
+                protocol: true,
+                required,
+                ..Default::default()
+            },
+        );
 
         env.borrow_mut().define(
-            &name,
+            name,
             Expression::Object(Object {
-                idx: self.env_arena.len() - 1,
-                parent: parent_idx,
+                idx,
+                name: name.clone(),
+                parent: None,
+                class: None,
+                record: None,
             }),
         )
     }
 
+    // whether a class or the class of an instance declares a protocol, directly or through a parent
+    fn eval_implements(
+        &mut self,
+        list: &[Expression],
+        env: &mut Rc<RefCell<Environment>>,
+    ) -> Result<Expression, Error> {
+        let [_tag, obj, protocol] = list else {
+            return Err(Error::Invalid("invalid implements? call".to_string()));
+        };
+
+        let protocol = self.eval_object_arg(protocol, env)?;
+        let Expression::Object(obj) = self.eval_exp(obj, env)? else {
+            return Ok(Expression::Boolean(false));
+        };
+
+        Ok(Expression::Boolean(self.class_chain(&obj).iter().any(
+            |idx| {
+                self.classes
+                    .get(idx)
+                    .is_some_and(|info| info.protocols.contains(&protocol.idx))
+            },
+        )))
+    }
+
     fn eval_new(
         &mut self,
         list: &[Expression],
@@ -314,34 +862,70 @@ impl Evaluator {
         ))?;
 
         if let Expression::Object(obj) = self.eval_exp(class_name, env)? {
+            if obj.class.is_some() {
+                return Err(Error::Invalid(
+                    "cannot create new instance of an instance".to_string(),
+                ));
+            }
+
+            if self.is_protocol(obj.idx) {
+                return Err(Error::Invalid(format!(
+                    "cannot create new instance of protocol {}",
+                    obj.name
+                )));
+            }
+
             let class_env = self.env_arena.get(obj.idx).unwrap();
-            let mut instance_env = Rc::new(RefCell::new(Environment::extend(class_env.clone())));
-
-            let constructor_fn = instance_env.borrow_mut().lookup("constructor")?;
-            if let Expression::Function(params, body, env_idx) = constructor_fn {
-                let mut rest = rest.to_vec();
-
-                rest.insert(0, Expression::Symbol("constructor".to_string())); // function name
-                rest.insert(
-                    1,
-                    Expression::Object(Object {
-                        idx: env_idx,
-                        parent: None,
-                    }),
-                ); // passing self
-
-                self.eval_function_body(&rest, params, body, env, &mut instance_env)?;
-
-                self.env_arena.push(instance_env);
-                Ok(Expression::Object(Object {
-                    idx: self.env_arena.len() - 1,
-                    parent: obj.parent,
-                }))
-            } else {
-                Err(Error::Reason(
-                    "cannot get valid constructor for class".to_string(),
-                ))
+            let instance_env = Rc::new(RefCell::new(Environment::extend(class_env.clone())));
+
+            // non static fields declared in class bodies are copied into every instance
+            for class_idx in self.class_chain(&obj).iter().rev() {
+                let info = self.classes.get(class_idx);
+                let defaults = self.env_arena[*class_idx]
+                    .borrow()
+                    .record
+                    .iter()
+                    .filter(|(name, value)| {
+                        !matches!(value, Expression::Function(..) | Expression::Object(..))
+                            && !info.is_some_and(|info| info.statics.contains(*name))
+                    })
+                    .map(|(name, value)| (name.clone(), value.clone()))
+                    .collect::<Vec<(String, Expression)>>();
+
+                for (name, value) in defaults {
+                    instance_env.borrow_mut().define(&name, value)?;
+                }
+            }
+
+            self.env_arena.push(instance_env.clone());
+            let instance = Expression::Object(Object {
+                idx: self.env_arena.len() - 1,
+                name: obj.name.clone(),
+                parent: None,
+                class: Some(Rc::new(RefCell::new(obj))),
+                record: Some(Record(instance_env.clone())),
+            });
+
+            let mut args = vec![instance.clone()]; // passing self
+            for arg in rest.iter() {
+                args.push(self.eval_exp(arg, env)?);
+            }
+
+            let constructor_fn = instance_env.borrow_mut().lookup("constructor");
+            match constructor_fn {
+                Ok(constructor_fn @ Expression::Function(..)) => {
+                    self.call_function(&constructor_fn, args)?;
+                }
+                // classes without constructor only accept empty instantiation
+                Err(_) if rest.is_empty() => (),
+                _ => {
+                    return Err(Error::Reason(
+                        "cannot get valid constructor for class".to_string(),
+                    ))
+                }
             }
+
+            Ok(instance)
         } else {
             Err(Error::Invalid(
                 "invalid creating new instance of class".to_string(),
@@ -364,8 +948,9 @@ impl Evaluator {
         };
 
         if let Expression::Object(obj) = self.eval_exp(instance, env)? {
-            let instance_env = self.env_arena.get_mut(obj.idx).unwrap();
-            instance_env.borrow_mut().lookup(&name)
+            self.check_private_access(&obj, &name, env, false)?;
+            self.check_export(&obj, &name, env)?;
+            self.lookup_prop(&obj, &name)
         } else {
             Err(Error::Reason(format!(
                 "{} is not a instance of a class",
@@ -374,6 +959,344 @@ impl Evaluator {
         }
     }
 
+    fn lookup_prop(&mut self, obj: &Object, name: &str) -> Result<Expression, Error> {
+        if let Some(getter) = self.find_accessor(obj, name, false) {
+            return self.call_function(&getter, vec![Expression::Object(obj.clone())]);
+        }
+
+        let instance_env = self.env_arena.get_mut(obj.idx).unwrap();
+        let value = instance_env.borrow_mut().lookup(name);
+
+        match (value, self.find_method(obj, "__get__")) {
+            // __get__ handles properties missing on the instance
+            (Err(_), Some(getter)) => self.call_function(
+                &getter,
+                vec![
+                    Expression::Object(obj.clone()),
+                    Expression::String(name.to_string()),
+                ],
+            ),
+            (value, _) => value,
+        }
+    }
+
+    // special method defined by the class chain of an instance
+    pub(crate) fn find_method(&self, obj: &Object, name: &str) -> Option<Expression> {
+        obj.class.as_ref()?;
+
+        self.object_envs(obj)
+            .iter()
+            .find_map(|env| match env.borrow().record.get(name) {
+                Some(method @ Expression::Function(..)) => Some(method.clone()),
+                _ => None,
+            })
+    }
+
+    // record of an instance, or the environment of a class or module
+    pub(crate) fn object_env(&self, obj: &Object) -> Result<Rc<RefCell<Environment>>, Error> {
+        self.env_arena
+            .get(obj.idx)
+            .cloned()
+            .ok_or(Error::Reason("cannot find object environment".to_string()))
+    }
+
+    // environments an object resolves properties through: the instance record (if any),
+    // its class and then every parent class
+    pub(crate) fn object_envs(&self, obj: &Object) -> Vec<Rc<RefCell<Environment>>> {
+        let mut envs = vec![];
+        if obj.class.is_some() {
+            envs.push(self.env_arena[obj.idx].clone());
+        }
+
+        for class_idx in self.class_chain(obj) {
+            envs.push(self.env_arena[class_idx].clone());
+        }
+
+        envs
+    }
+
+    // class of an instance (or the class itself) followed by every parent class
+    fn class_chain(&self, obj: &Object) -> Vec<usize> {
+        let mut chain = vec![];
+
+        let mut class = match &obj.class {
+            Some(class) => Some(class.clone()),
+            None => {
+                chain.push(obj.idx);
+                obj.parent.clone()
+            }
+        };
+
+        while let Some(current) = class {
+            chain.push(current.borrow().idx);
+            class = current.borrow().parent.clone();
+        }
+
+        chain
+    }
+
+    // nearest class whose body or methods enclose the environment
+    fn enclosing_class(&self, env: &Rc<RefCell<Environment>>) -> Option<usize> {
+        let mut current = Some(env.clone());
+
+        while let Some(env) = current {
+            if let Some(idx) = self.class_of_env(&env) {
+                return Some(idx);
+            }
+            current = env.borrow().parent.clone();
+        }
+
+        None
+    }
+
+    // class whose own environment is env, i.e. env is a class body
+    fn class_of_env(&self, env: &Rc<RefCell<Environment>>) -> Option<usize> {
+        self.classes
+            .keys()
+            .find(|idx| Rc::ptr_eq(&self.env_arena[**idx], env))
+            .copied()
+    }
+
+    fn find_accessor(&self, obj: &Object, name: &str, setter: bool) -> Option<Expression> {
+        self.class_chain(obj).iter().find_map(|idx| {
+            let info = self.classes.get(idx)?;
+            match setter {
+                true => info.setters.get(name).cloned(),
+                false => info.getters.get(name).cloned(),
+            }
+        })
+    }
+
+    // #names are only reachable from methods of the class declaring them,
+    // assigning an undeclared one from a method declares it for that class
+    fn check_private_access(
+        &mut self,
+        obj: &Object,
+        name: &str,
+        env: &Rc<RefCell<Environment>>,
+        declare: bool,
+    ) -> Result<(), Error> {
+        if !name.starts_with('#') {
+            return Ok(());
+        }
+
+        let chain = self.class_chain(obj);
+        let declaring = chain
+            .iter()
+            .find(|idx| {
+                self.classes
+                    .get(idx)
+                    .is_some_and(|info| info.privates.contains(name))
+            })
+            .copied();
+
+        match (self.enclosing_class(env), declaring) {
+            (Some(current), Some(declaring)) if current == declaring => Ok(()),
+            (Some(current), None) if chain.contains(&current) => {
+                if declare {
+                    if let Some(info) = self.classes.get_mut(&current) {
+                        info.privates.insert(name.to_string());
+                    }
+                }
+                Ok(())
+            }
+            _ => Err(Error::Reference(format!(
+                "{} is private and only accessible inside its class",
+                name
+            ))),
+        }
+    }
+
+    // (static var name value) or (static def name params body) inside a class body
+    fn eval_static(
+        &mut self,
+        list: &[Expression],
+        env: &mut Rc<RefCell<Environment>>,
+    ) -> Result<Expression, Error> {
+        let definition = &list[1..];
+        let Some(Expression::Symbol(name)) = definition.get(1) else {
+            return Err(Error::Invalid("invalid static declaration".to_string()));
+        };
+
+        let class_idx = self.class_of_env(env).ok_or(Error::Invalid(
+            "static members can only be declared in a class body".to_string(),
+        ))?;
+
+        let res = self.eval_exp(&Expression::List(definition.to_vec()), env)?;
+        if let Some(info) = self.classes.get_mut(&class_idx) {
+            info.statics.insert(name.clone());
+        }
+
+        Ok(res)
+    }
+
+    // (get name (self) body) or (set name (self value) body) inside a class body
+    fn eval_define_accessor(
+        &mut self,
+        list: &[Expression],
+        env: &mut Rc<RefCell<Environment>>,
+    ) -> Result<Expression, Error> {
+        let [tag, Expression::Symbol(name), params, body] = list else {
+            return Err(Error::Invalid("invalid accessor definition".to_string()));
+        };
+
+        let class_idx = self.class_of_env(env).ok_or(Error::Invalid(
+            "accessors can only be defined in a class body".to_string(),
+        ))?;
+
+        let func = self.eval_define_lambda(
+            &[
+                Expression::Symbol("lambda".to_string()),
+                params.clone(),
+                body.clone(),
+            ],
+            env,
+        )?;
+
+        let info = self.classes.get_mut(&class_idx).unwrap();
+        match tag {
+            Expression::Symbol(tag) if tag == "set" => info.setters.insert(name.clone(), func),
+            _ => info.getters.insert(name.clone(), func),
+        };
+
+        Ok(Expression::Void)
+    }
+
+    fn eval_object_arg(
+        &mut self,
+        exp: &Expression,
+        env: &mut Rc<RefCell<Environment>>,
+    ) -> Result<Object, Error> {
+        match self.eval_exp(exp, env)? {
+            Expression::Object(obj) => Ok(obj),
+            other => Err(Error::Reason(format!(
+                "{} is not a instance of a class",
+                other
+            ))),
+        }
+    }
+
+    // string representation of a value, instances are printed through __str__ or with their fields
+    pub fn stringify(&mut self, exp: &Expression) -> Result<String, Error> {
+        self.stringify_value(exp, &mut vec![])
+    }
+
+    fn stringify_value(
+        &mut self,
+        exp: &Expression,
+        seen: &mut Vec<usize>,
+    ) -> Result<String, Error> {
+        match exp {
+            Expression::List(list) => Ok(format!(
+                "({})",
+                list.iter()
+                    .map(|exp| self.stringify_value(exp, seen))
+                    .collect::<Result<Vec<String>, Error>>()?
+                    .join(" ")
+            )),
+            Expression::Map(map) => Ok(format!(
+                "{{{}}}",
+                map.iter()
+                    .map(|(key, value)| Ok(format!(
+                        "{}: {}",
+                        key,
+                        self.stringify_value(value, seen)?
+                    )))
+                    .collect::<Result<Vec<String>, Error>>()?
+                    .join(", ")
+            )),
+            Expression::Object(obj) if self.find_method(obj, "__str__").is_some() => {
+                let to_str = self.find_method(obj, "__str__").unwrap();
+                match self.call_function(&to_str, vec![exp.clone()])? {
+                    Expression::String(str) => Ok(str),
+                    other => self.stringify_value(&other, seen),
+                }
+            }
+            Expression::Object(obj) if obj.class.is_some() && !seen.contains(&obj.idx) => {
+                seen.push(obj.idx);
+
+                let fields = self
+                    .instance_fields(obj)
+                    .iter()
+                    .map(|(name, value)| {
+                        Ok(format!("{}: {}", name, self.stringify_value(value, seen)?))
+                    })
+                    .collect::<Result<Vec<String>, Error>>()?
+                    .join(", ");
+
+                seen.pop();
+                Ok(format!("<{} instance {{{}}}>", obj.name, fields))
+            }
+            other => Ok(other.to_string()),
+        }
+    }
+
+    // public data fields of an instance sorted by name, private # fields and methods are left out
+    pub(crate) fn instance_fields(&self, obj: &Object) -> Vec<(String, Expression)> {
+        let mut fields = self.env_arena[obj.idx]
+            .borrow()
+            .record
+            .iter()
+            .filter(|(name, value)| {
+                !name.starts_with('#') && !matches!(value, Expression::Function(..))
+            })
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect::<Vec<(String, Expression)>>();
+        fields.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        fields
+    }
+
+    // (. instance method args...) calls the method with instance passed as self
+    fn eval_method_call(
+        &mut self,
+        list: &[Expression],
+        env: &mut Rc<RefCell<Environment>>,
+    ) -> Result<Expression, Error> {
+        let [_tag, receiver, method, args @ ..] = list else {
+            return Err(Error::Invalid("invalid method call".to_string()));
+        };
+
+        let method = match method {
+            Expression::Symbol(name) => name.clone(),
+            _ => return Err(Error::Invalid("invalid method name".to_string())),
+        };
+
+        let target = self.eval_exp(receiver, env)?;
+
+        // methods reached through super still run against the current self
+        let self_value = match receiver {
+            Expression::List(super_call)
+                if super_call.first() == Some(&Expression::Symbol("super".to_string())) =>
+            {
+                let current_self = env.borrow_mut().lookup("self");
+                match current_self {
+                    Ok(self_value) => self_value,
+                    Err(_) => self.eval_exp(&super_call[1], env)?,
+                }
+            }
+            _ => target.clone(),
+        };
+
+        let Expression::Object(obj) = target else {
+            return Err(Error::Reason(format!(
+                "{} is not a instance of a class",
+                receiver
+            )));
+        };
+
+        self.check_private_access(&obj, &method, env, false)?;
+        self.check_export(&obj, &method, env)?;
+        let func = self.lookup_prop(&obj, &method)?;
+
+        let mut call_args = vec![self_value];
+        for arg in args {
+            call_args.push(self.eval_exp(arg, env)?);
+        }
+
+        self.call_function(&func, call_args)
+    }
+
     fn eval_super(
         &mut self,
         list: &[Expression],
@@ -384,8 +1307,15 @@ impl Evaluator {
         };
 
         if let Expression::Object(obj) = self.eval_exp(class_name, env)? {
+            // super of an instance is the parent of its class
+            let class = match obj.class {
+                Some(class) => class.borrow().clone(),
+                None => obj,
+            };
+
             Ok(Expression::Object(
-                obj.parent
+                class
+                    .parent
                     .ok_or(Error::Reason("cannot find parent".to_string()))?
                     .borrow()
                     .clone(),
@@ -397,6 +1327,87 @@ impl Evaluator {
         }
     }
 
+    // call a function value with already evaluated arguments
+    pub fn call_function(
+        &mut self,
+        func: &Expression,
+        args: Vec<Expression>,
+    ) -> Result<Expression, Error> {
+        if let Expression::Function(params, body, env_idx) = func {
+            if params.len() != args.len() {
+                return Err(Error::Reason("incorrect argurments number".to_string()));
+            }
+
+            let mut activation_env = Rc::new(RefCell::new(Environment::extend(
+                self.env_arena
+                    .get(*env_idx)
+                    .ok_or(Error::Reason("unexpected error".to_string()))?
+                    .clone(),
+            )));
+
+            for (param_name, arg) in params.iter().zip(args) {
+                activation_env.borrow_mut().define(param_name, arg)?;
+            }
+
+            self.eval_exp(&body.borrow(), &mut activation_env)
+        } else if let Expression::Native(name) = func {
+            let native = builtins::lookup(name).ok_or(Error::Reference(format!(
+                "{} is not a native function",
+                name
+            )))?;
+            native(self, args)
+        } else if let Some(call) = match func {
+            Expression::Object(obj) => self.find_method(obj, "__call__"),
+            _ => None,
+        } {
+            let mut call_args = vec![func.clone()];
+            call_args.extend(args);
+            self.call_function(&call, call_args)
+        } else {
+            Err(Error::Invalid(format!("{} is not a function", func)))
+        }
+    }
+
+    // calling an instance invokes its __call__ method
+    fn eval_call_object(
+        &mut self,
+        obj: Object,
+        args: &[Expression],
+        env: &mut Rc<RefCell<Environment>>,
+    ) -> Result<Expression, Error> {
+        let args = args
+            .iter()
+            .map(|arg| self.eval_exp(arg, env))
+            .collect::<Result<Vec<Expression>, Error>>()?;
+
+        self.call_function(&Expression::Object(obj), args)
+    }
+
+    fn eval_call_native(
+        &mut self,
+        name: &str,
+        args: &[Expression],
+        env: &mut Rc<RefCell<Environment>>,
+    ) -> Result<Expression, Error> {
+        let args = args
+            .iter()
+            .map(|arg| self.eval_exp(arg, env))
+            .collect::<Result<Vec<Expression>, Error>>()?;
+
+        let outer = self.native_env.replace(env.clone());
+        let res = self.call_function(&Expression::Native(name.to_string()), args);
+        self.native_env = outer;
+        res
+    }
+
+    fn eval_quote(&mut self, list: &[Expression]) -> Result<Expression, Error> {
+        let [_tag, exp] = list else {
+            return Err(Error::Invalid("invalid quote".to_string()));
+        };
+
+        Ok(exp.clone())
+    }
+
     // block: sequence of expression
     fn eval_block(
         &mut self,
@@ -463,6 +1474,128 @@ impl Evaluator {
         }
     }
 
+    // (try body (catch err handler)), err is bound to a map with the kind and message of the error
+    fn eval_try(
+        &mut self,
+        list: &[Expression],
+        env: &mut Rc<RefCell<Environment>>,
+    ) -> Result<Expression, Error> {
+        let [_tag, body, Expression::List(catch)] = list else {
+            return Err(Error::Invalid("invalid try statement".to_string()));
+        };
+
+        let [Expression::Symbol(tag), Expression::Symbol(name), handler] = catch.as_slice() else {
+            return Err(Error::Invalid("invalid catch clause".to_string()));
+        };
+        if tag != "catch" {
+            return Err(Error::Invalid("invalid catch clause".to_string()));
+        }
+
+        match self.eval_exp(body, env) {
+            Ok(value) => Ok(value),
+            Err(Error::Exit(code)) => Err(Error::Exit(code)),
+            Err(err) => {
+                let mut catch_env = Rc::new(RefCell::new(Environment::extend(env.clone())));
+                catch_env.borrow_mut().define(name, error_map(&err))?;
+
+                self.eval_exp(handler, &mut catch_env)
+            }
+        }
+    }
+
+    // (deftest name body...) declares a test, the test runner evaluates it later, tests of
+    // imported modules are ignored
+    fn eval_deftest(
+        &mut self,
+        list: &[Expression],
+        env: &mut Rc<RefCell<Environment>>,
+    ) -> Result<Expression, Error> {
+        let (name, body) = match list {
+            [_tag, Expression::String(name) | Expression::Symbol(name), body @ ..]
+                if !body.is_empty() =>
+            {
+                (name.clone(), body)
+            }
+            _ => return Err(Error::Invalid("invalid deftest".to_string())),
+        };
+
+        if self.import_stack.is_empty() {
+            let mut block = vec![Expression::Symbol("begin".to_string())];
+            block.extend_from_slice(body);
+            self.tests.push(TestCase {
+                name,
+                body: Expression::List(block),
+                env: env.clone(),
+            });
+        }
+
+        Ok(Expression::Void)
+    }
+
+    // (assert-throws body) or (assert-throws body kind) fails unless body raises an error,
+    // of the given kind if any, and returns the error map like catch
+    fn eval_assert_throws(
+        &mut self,
+        list: &[Expression],
+        env: &mut Rc<RefCell<Environment>>,
+    ) -> Result<Expression, Error> {
+        let (body, kind) = match list {
+            [_tag, body] => (body, None),
+            [_tag, body, kind] => match self.eval_exp(kind, env)? {
+                Expression::String(kind) => (body, Some(kind)),
+                other => {
+                    return Err(Error::Invalid(format!(
+                        "assert-throws expects an error kind string, got {}",
+                        other
+                    )))
+                }
+            },
+            _ => return Err(Error::Invalid("invalid assert-throws".to_string())),
+        };
+
+        match self.eval_exp(body, env) {
+            Err(Error::Exit(code)) => Err(Error::Exit(code)),
+            Err(err) => match kind {
+                Some(kind) if kind != err.kind() => Err(Error::Assertion(format!(
+                    "assert-throws failed\n  expected: {} error\n  actual:   {} error: {}",
+                    kind,
+                    err.kind(),
+                    err.message()
+                ))),
+                _ => Ok(error_map(&err)),
+            },
+            Ok(value) => Err(Error::Assertion(format!(
+                "assert-throws failed\n  expected: {} error\n  actual:   returned {}",
+                kind.as_deref().unwrap_or("an"),
+                format::repr_of(self, &value)?
+            ))),
+        }
+    }
+
+    // (throw message) raises an error, a caught error map is raised again with its message
+    fn eval_throw(
+        &mut self,
+        list: &[Expression],
+        env: &mut Rc<RefCell<Environment>>,
+    ) -> Result<Expression, Error> {
+        let [_tag, value] = list else {
+            return Err(Error::Invalid("invalid throw statement".to_string()));
+        };
+
+        // a caught error map keeps its kind when thrown again
+        match self.eval_exp(value, env)? {
+            Expression::String(message) => Err(Error::Thrown(message)),
+            Expression::Map(map) if map.contains_key("message") => {
+                let message = self.stringify(&map["message"])?;
+                match map.get("kind") {
+                    Some(Expression::String(kind)) => Err(Error::from_kind(kind, message)),
+                    _ => Err(Error::Thrown(message)),
+                }
+            }
+            other => Err(Error::Thrown(self.stringify(&other)?)),
+        }
+    }
+
     fn eval_define_function(
         &mut self,
         list: &[Expression],
@@ -536,7 +1669,7 @@ impl Evaluator {
 
     fn eval_define_variable(
         &mut self,
-        list: &Vec<Expression>,
+        list: &[Expression],
         env: &mut Rc<RefCell<Environment>>,
     ) -> Result<Expression, Error> {
         use Expression::Symbol;
@@ -558,6 +1691,10 @@ impl Evaluator {
         list: &[Expression],
         env: &mut Rc<RefCell<Environment>>,
     ) -> Result<Expression, Error> {
+        if list.len() == 4 {
+            return self.eval_define_accessor(list, env);
+        }
+
         let [_tag, reference, value] = &list else {
             return Err(Error::Invalid("invalid set statement".to_string()));
         };
@@ -577,7 +1714,36 @@ impl Evaluator {
                     let value = self.eval_exp(value, env)?;
 
                     if let Expression::Object(obj) = self.eval_exp(instance, env)? {
-                        let instance_env = self.env_arena.get_mut(obj.idx).unwrap();
+                        self.check_private_access(&obj, &prop_name, env, true)?;
+                        self.check_export(&obj, &prop_name, env)?;
+
+                        if let Some(setter) = self.find_accessor(&obj, &prop_name, true) {
+                            self.call_function(
+                                &setter,
+                                vec![Expression::Object(obj), value.clone()],
+                            )?;
+                            return Ok(value);
+                        }
+
+                        if self.find_accessor(&obj, &prop_name, false).is_some() {
+                            return Err(Error::Reason(format!(
+                                "property {} has no setter",
+                                prop_name
+                            )));
+                        }
+
+                        // static members are shared through the class declaring them
+                        let target_idx = self
+                            .class_chain(&obj)
+                            .into_iter()
+                            .find(|idx| {
+                                self.classes
+                                    .get(idx)
+                                    .is_some_and(|info| info.statics.contains(&prop_name))
+                            })
+                            .unwrap_or(obj.idx);
+
+                        let instance_env = self.env_arena.get_mut(target_idx).unwrap();
                         instance_env.borrow_mut().define(&prop_name, value)
                     } else {
                         Err(Error::Reason(format!(
@@ -605,7 +1771,7 @@ impl Evaluator {
             let mut res = String::new();
             for arg in args {
                 let exp = self.eval_exp(arg, env)?;
-                res += &exp.to_string();
+                res += &self.stringify(&exp)?;
             }
             println!("{}", res);
 
@@ -615,6 +1781,60 @@ impl Evaluator {
         }
     }
 
+    // dispatch operators on instances to their special methods
+    fn eval_overloaded_op(
+        &mut self,
+        op: &str,
+        left: &Expression,
+        right: &Expression,
+    ) -> Result<Option<Expression>, Error> {
+        use Expression::*;
+
+        let (method, reflected) = match op {
+            "+" => ("__add__", "__radd__"),
+            "-" => ("__sub__", "__rsub__"),
+            "*" => ("__mul__", "__rmul__"),
+            "/" => ("__div__", "__rdiv__"),
+            "%" => ("__mod__", "__rmod__"),
+            "^" => ("__pow__", "__rpow__"),
+            "=" => ("__eq__", "__eq__"),
+            "!=" => ("__ne__", "__ne__"),
+            "<" => ("__lt__", "__gt__"),
+            "<=" => ("__le__", "__ge__"),
+            ">" => ("__gt__", "__lt__"),
+            ">=" => ("__ge__", "__le__"),
+            _ => return Ok(None),
+        };
+
+        if let Object(obj) = left {
+            if let Some(func) = self.find_method(obj, method) {
+                return self
+                    .call_function(&func, vec![left.clone(), right.clone()])
+                    .map(Some);
+            }
+        }
+
+        if let Object(obj) = right {
+            if let Some(func) = self.find_method(obj, reflected) {
+                return self
+                    .call_function(&func, vec![right.clone(), left.clone()])
+                    .map(Some);
+            }
+        }
+
+        match (op, left, right) {
+            ("!=", ..) => Ok(match self.eval_overloaded_op("=", left, right)? {
+                Some(Boolean(eq)) => Some(Boolean(!eq)),
+                Some(Void) => Some(Boolean(true)),
+                Some(_) => Some(Boolean(false)),
+                None => None,
+            }),
+            // objects without __eq__ are compared by identity
+            ("=", Object(left), Object(right)) => Ok(Some(Boolean(left.idx == right.idx))),
+            _ => Ok(None),
+        }
+    }
+
     fn eval_binary_op(
         &mut self,
         list: &[Expression],
@@ -626,6 +1846,12 @@ impl Evaluator {
         let left = self.eval_exp(&list[1], env)?;
         let right = self.eval_exp(&list[2], env)?;
 
+        if let Symbol(op) = head {
+            if let Some(res) = self.eval_overloaded_op(op, &left, &right)? {
+                return Ok(res);
+            }
+        }
+
         match head {
             Expression::Symbol(str) => match str.as_str() {
                 "+" => match (left, right) {
@@ -640,6 +1866,9 @@ impl Evaluator {
                         if left_val { 1.0 } else { 0.0 } + if right_val { 1.0 } else { 0.0 },
                     )),
                     (String(left_val), String(right_val)) => Ok(String(left_val + &right_val)),
+                    (DateTime(dt), Number(ms)) | (Number(ms), DateTime(dt)) => {
+                        Ok(DateTime(builtins::time::shift(&dt, ms)?))
+                    }
                     _ => Err(Error::Invalid("invalid type for + operator".to_string())),
                 },
                 "-" => match (left, right) {
@@ -653,6 +1882,10 @@ impl Evaluator {
                     (Boolean(left_val), Boolean(right_val)) => Ok(Number(
                         if left_val { 1.0 } else { 0.0 } - if right_val { 1.0 } else { 0.0 },
                     )),
+                    (DateTime(dt), Number(ms)) => Ok(DateTime(builtins::time::shift(&dt, -ms)?)),
+                    (DateTime(left_val), DateTime(right_val)) => {
+                        Ok(Number(builtins::time::between(&left_val, &right_val)))
+                    }
                     _ => Err(Error::Invalid("invalid type for - operator".to_string())),
                 },
                 "*" => match (left, right) {
@@ -719,6 +1952,7 @@ impl Evaluator {
                     (String(left_val), String(right_val)) => {
                         Ok(Boolean(left_val.cmp(&right_val) == Ordering::Greater))
                     }
+                    (DateTime(left_val), DateTime(right_val)) => Ok(Boolean(left_val > right_val)),
                     _ => Err(Error::Invalid("invalid type for > operator".to_string())),
                 },
 
@@ -733,6 +1967,7 @@ impl Evaluator {
                     (Boolean(left_val), Boolean(right_val)) => Ok(Boolean(
                         if left_val { 1.0 } else { 0.0 } >= if right_val { 1.0 } else { 0.0 },
                     )),
+                    (DateTime(left_val), DateTime(right_val)) => Ok(Boolean(left_val >= right_val)),
                     _ => Err(Error::Invalid("invalid type for >= operator".to_string())),
                 },
                 "<" => match (left, right) {
@@ -749,6 +1984,7 @@ impl Evaluator {
                     (String(left_val), String(right_val)) => {
                         Ok(Boolean(left_val.cmp(&right_val) == Ordering::Less))
                     }
+                    (DateTime(left_val), DateTime(right_val)) => Ok(Boolean(left_val < right_val)),
                     _ => Err(Error::Invalid("invalid type for < operator".to_string())),
                 },
 
@@ -763,6 +1999,7 @@ impl Evaluator {
                     (Boolean(left_val), Boolean(right_val)) => Ok(Boolean(
                         if left_val { 1.0 } else { 0.0 } <= if right_val { 1.0 } else { 0.0 },
                     )),
+                    (DateTime(left_val), DateTime(right_val)) => Ok(Boolean(left_val <= right_val)),
                     _ => Err(Error::Invalid("invalid type for <= operator".to_string())),
                 },
                 "=" => match (left, right) {
@@ -779,6 +2016,10 @@ impl Evaluator {
                     (String(left_val), String(right_val)) => {
                         Ok(Boolean(left_val.cmp(&right_val) == Ordering::Equal))
                     }
+                    (Void, Void) => Ok(Boolean(true)),
+                    (List(left_val), List(right_val)) => Ok(Boolean(left_val == right_val)),
+                    (Map(left_val), Map(right_val)) => Ok(Boolean(left_val == right_val)),
+                    (DateTime(left_val), DateTime(right_val)) => Ok(Boolean(left_val == right_val)),
                     _ => Err(Error::Invalid("invalid type for == operator".to_string())),
                 },
 
@@ -796,6 +2037,10 @@ impl Evaluator {
                     (String(left_val), String(right_val)) => {
                         Ok(Boolean(left_val.cmp(&right_val) != Ordering::Equal))
                     }
+                    (Void, Void) => Ok(Boolean(false)),
+                    (List(left_val), List(right_val)) => Ok(Boolean(left_val != right_val)),
+                    (Map(left_val), Map(right_val)) => Ok(Boolean(left_val != right_val)),
+                    (DateTime(left_val), DateTime(right_val)) => Ok(Boolean(left_val != right_val)),
                     _ => Err(Error::Invalid("invalid type for != operator".to_string())),
                 },
 
@@ -814,3 +2059,47 @@ impl Evaluator {
         }
     }
 }
+
+// names of (import (a (b as c) d as e) Mod) paired with the names they are bound to
+fn parse_import_names(names: &[Expression]) -> Result<Vec<(String, String)>, Error> {
+    let mut res = vec![];
+    let mut idx = 0;
+
+    while idx < names.len() {
+        match (&names[idx], names.get(idx + 1), names.get(idx + 2)) {
+            (
+                Expression::Symbol(name),
+                Some(Expression::Symbol(tag)),
+                Some(Expression::Symbol(alias)),
+            ) if tag == "as" => {
+                res.push((name.clone(), alias.clone()));
+                idx += 3;
+            }
+            (Expression::Symbol(name), ..) => {
+                res.push((name.clone(), name.clone()));
+                idx += 1;
+            }
+            (Expression::List(aliased), ..) => {
+                res.extend(parse_import_names(aliased)?);
+                idx += 1;
+            }
+            _ => return Err(Error::Reason("invalid import".to_string())),
+        }
+    }
+
+    Ok(res)
+}
+
+// error as seen by catch, a map with its kind and message
+fn error_map(err: &Error) -> Expression {
+    Expression::Map(BTreeMap::from([
+        (
+            "kind".to_string(),
+            Expression::String(err.kind().to_string()),
+        ),
+        (
+            "message".to_string(),
+            Expression::String(err.message().to_string()),
+        ),
+    ]))
+}
@@ -1,4 +1,13 @@
-use std::{cell::RefCell, fmt::Display, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    fmt::{Debug, Display},
+    rc::Rc,
+};
+
+use chrono::{FixedOffset, SecondsFormat};
+
+use crate::environment::Environment;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Expression {
@@ -14,17 +23,51 @@ pub enum Expression {
         usize, // env
     ),
     Object(Object), // oop and modules
+    Map(BTreeMap<String, Expression>),
+    Native(String), // function implemented in rust, qualified name like strings.upper
+    Regex(Regex),
+    DateTime(chrono::DateTime<FixedOffset>), // instant with the utc offset it is shown in
+}
+
+// compiled regular expression, two regexes are equal when their patterns are
+#[derive(Debug, Clone)]
+pub struct Regex(pub regex::Regex);
+
+impl PartialEq for Regex {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Object {
     pub idx: usize,
-    pub parent: Option<Rc<RefCell<Object>>>,
+    pub name: String, // class or module name, instances carry the name of their class
+    pub parent: Option<Rc<RefCell<Object>>>, // parent class
+    pub class: Option<Rc<RefCell<Object>>>, // class of an instance, none for classes and modules
+    pub record: Option<Record>, // fields of an instance, none for classes and modules
 }
 
-impl Display for Expression {
+// environment holding the fields of an instance, two records are equal when they are the same
+#[derive(Clone)]
+pub struct Record(pub Rc<RefCell<Environment>>);
+
+impl PartialEq for Record {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Debug for Record {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let str = match self {
+        write!(f, "Record")
+    }
+}
+
+impl Expression {
+    // seen holds the instances being printed, an instance reached again is not expanded
+    fn display(&self, seen: &mut Vec<usize>) -> String {
+        match self {
             Expression::Void => "nil".to_string(),
             Expression::Boolean(bool) => bool.to_string(),
             Expression::Number(num) => num.to_string(),
@@ -34,7 +77,7 @@ impl Display for Expression {
                 let mut str = "(".to_string();
                 str += &list
                     .iter()
-                    .map(|exp| exp.to_string())
+                    .map(|exp| exp.display(seen))
                     .collect::<Vec<String>>()
                     .join(" ");
 
@@ -42,8 +85,51 @@ impl Display for Expression {
                 str.to_string()
             }
             Expression::Function(params, _, _) => format!("fn({})", params.join(", ")).to_string(),
-            Expression::Object(..) => "class ".to_string(),
-        };
-        write!(f, "{}", str)
+            Expression::Map(map) => format!(
+                "{{{}}}",
+                map.iter()
+                    .map(|(key, value)| format!("{}: {}", key, value.display(seen)))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            Expression::Native(name) => format!("<native fn {}>", name),
+            Expression::Regex(re) => format!("<regex {}>", re.0.as_str()),
+            Expression::DateTime(dt) => dt.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+            Expression::Object(obj) => match (&obj.class, &obj.record) {
+                (Some(_), Some(record)) if !seen.contains(&obj.idx) => {
+                    let Ok(record) = record.0.try_borrow() else {
+                        return format!("<{} instance>", obj.name);
+                    };
+
+                    // public data fields sorted by name, like Evaluator::stringify
+                    let mut fields = record
+                        .record
+                        .iter()
+                        .filter(|(name, value)| {
+                            !name.starts_with('#') && !matches!(value, Expression::Function(..))
+                        })
+                        .collect::<Vec<(&String, &Expression)>>();
+                    fields.sort_by_key(|(name, _)| *name);
+
+                    seen.push(obj.idx);
+                    let fields = fields
+                        .into_iter()
+                        .map(|(name, value)| format!("{}: {}", name, value.display(seen)))
+                        .collect::<Vec<String>>()
+                        .join(", ");
+                    seen.pop();
+
+                    format!("<{} instance {{{}}}>", obj.name, fields)
+                }
+                (Some(_), _) => format!("<{} instance>", obj.name),
+                (None, _) => format!("<class {}>", obj.name),
+            },
+        }
+    }
+}
+
+impl Display for Expression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.display(&mut vec![]))
     }
 }
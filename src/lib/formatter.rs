@@ -0,0 +1,213 @@
+use crate::{
+    cst::{self, parse_cst, Trivia, TriviaKind},
+    error::Error,
+    expression::Expression,
+    parser::parse,
+};
+
+pub const DEFAULT_WIDTH: usize = 80;
+
+// what the layout works on, atoms keep their source text
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Atom(String),
+    List(Vec<Node>),
+    Comment(String, bool), // text and whether it follows code on the same line
+    Blank,                 // empty lines between two forms
+}
+
+// rewrites a program in the canonical layout, comments are kept and runs of empty lines
+// become one
+pub fn format_source(source: &str, width: usize) -> Result<String, Error> {
+    let original = parse(source)?;
+    let tree = parse_cst(source)?;
+
+    let (mut newlines, mut nodes) = (1, vec![]);
+    layout_nodes(&tree.items, &mut newlines, &mut nodes);
+    layout_trivia(&tree.eof.leading, &mut newlines, &mut nodes);
+
+    let mut out = String::new();
+    write_items(&nodes, 0, width, &mut out);
+    let out = out.trim_start_matches('\n').to_string() + "\n";
+
+    // the layout must never change what the program means
+    if !same_tree(&parse(&out)?, &original) {
+        return Err(Error::Reason(
+            "formatting changed the meaning of the program".to_string(),
+        ));
+    }
+
+    Ok(match out.trim().is_empty() {
+        true => String::new(),
+        false => out,
+    })
+}
+
+// structural equality where nan equals nan, which PartialEq on f64 never gives
+fn same_tree(left: &Expression, right: &Expression) -> bool {
+    match (left, right) {
+        (Expression::Number(left), Expression::Number(right)) => left.to_bits() == right.to_bits(),
+        (Expression::List(left), Expression::List(right)) => {
+            left.len() == right.len() && left.iter().zip(right).all(|(l, r)| same_tree(l, r))
+        }
+        (left, right) => left == right,
+    }
+}
+
+// layout nodes of cst nodes, the comments and empty lines come from their trivia
+fn layout_nodes(
+    items: &[cst::Node],
+    newlines: &mut usize, // line breaks since the last node
+    nodes: &mut Vec<Node>,
+) {
+    for item in items {
+        match item {
+            cst::Node::Atom(token) => {
+                layout_trivia(&token.leading, newlines, nodes);
+                push_node(Node::Atom(token.text.clone()), newlines, nodes);
+                layout_trivia(&token.trailing, newlines, nodes);
+            }
+            cst::Node::List { open, items, close } => {
+                layout_trivia(&open.leading, newlines, nodes);
+
+                let (mut inner_newlines, mut inner) = (0, vec![]);
+                layout_trivia(&open.trailing, &mut inner_newlines, &mut inner);
+                layout_nodes(items, &mut inner_newlines, &mut inner);
+                layout_trivia(&close.leading, &mut inner_newlines, &mut inner);
+
+                push_node(Node::List(inner), newlines, nodes);
+                layout_trivia(&close.trailing, newlines, nodes);
+            }
+        }
+    }
+}
+
+fn layout_trivia(trivia: &[Trivia], newlines: &mut usize, nodes: &mut Vec<Node>) {
+    for trivia in trivia {
+        match trivia.kind {
+            TriviaKind::Newline => *newlines += 1,
+            TriviaKind::Comment => {
+                let trailing = *newlines == 0;
+                push_node(
+                    Node::Comment(trivia.text.trim_end().to_string(), trailing),
+                    newlines,
+                    nodes,
+                );
+            }
+            TriviaKind::Whitespace => (),
+        }
+    }
+}
+
+fn push_node(node: Node, newlines: &mut usize, nodes: &mut Vec<Node>) {
+    if *newlines > 1 && !nodes.is_empty() {
+        nodes.push(Node::Blank);
+    }
+    *newlines = 0;
+    nodes.push(node);
+}
+
+// number of arguments kept on the line of the head when a form is broken over lines
+fn head_args(head: &str) -> usize {
+    match head {
+        "for" | "static" => 3,
+        "def" | "class" | "get" | "deftest" => 2,
+        "var" | "set" | "if" | "while" | "lambda" | "module" | "mixin" | "protocol" | "try"
+        | "catch" | "import" => 1,
+        _ => 0,
+    }
+}
+
+// single line layout, none for lists holding comments or multi-line strings
+fn flat(node: &Node) -> Option<String> {
+    match node {
+        Node::Atom(text) if !text.contains('\n') => Some(text.clone()),
+        Node::List(items) => Some(format!(
+            "({})",
+            items
+                .iter()
+                .map(flat)
+                .collect::<Option<Vec<String>>>()?
+                .join(" ")
+        )),
+        _ => None,
+    }
+}
+
+fn write_node(node: &Node, indent: usize, width: usize, out: &mut String) {
+    if let Some(text) = flat(node).filter(|text| indent + text.chars().count() <= width) {
+        *out += &text;
+        return;
+    }
+
+    let Node::List(items) = node else {
+        if let Node::Atom(text) = node {
+            *out += text;
+        }
+        return;
+    };
+
+    out.push('(');
+    let Some(head) = items
+        .first()
+        .filter(|head| !matches!(head, Node::Comment(..) | Node::Blank))
+    else {
+        write_items(items, indent + 1, width, out);
+        close_list(items, indent, out);
+        return;
+    };
+    write_node(head, indent + 1, width, out);
+
+    // distinguished arguments stay on the first line while they fit
+    let mut rest = &items[1..];
+    if let Node::Atom(name) = head {
+        let mut column = indent + 1 + name.chars().count();
+        for _ in 0..head_args(name) {
+            let Some(text) = rest.first().and_then(flat) else {
+                break;
+            };
+            if column + 1 + text.chars().count() > width {
+                break;
+            }
+            column += 1 + text.chars().count();
+            out.push(' ');
+            *out += &text;
+            rest = &rest[1..];
+        }
+    }
+
+    write_items(rest, indent + 2, width, out);
+    close_list(items, indent, out);
+}
+
+// a comment ends its line so a paren after it goes on the next one
+fn close_list(items: &[Node], indent: usize, out: &mut String) {
+    if matches!(items.last(), Some(Node::Comment(..))) {
+        out.push('\n');
+        *out += &" ".repeat(indent);
+    }
+    out.push(')');
+}
+
+// each item on its own line at indent, trailing comments stay on the line they follow
+fn write_items(items: &[Node], indent: usize, width: usize, out: &mut String) {
+    for (idx, item) in items.iter().enumerate() {
+        match item {
+            Node::Blank if idx + 1 < items.len() => out.push('\n'),
+            Node::Blank => (),
+            Node::Comment(text, true) => {
+                out.push(' ');
+                *out += text;
+            }
+            item => {
+                out.push('\n');
+                *out += &" ".repeat(indent);
+                if let Node::Comment(text, _) = item {
+                    *out += text;
+                } else {
+                    write_node(item, indent, width, out);
+                }
+            }
+        }
+    }
+}
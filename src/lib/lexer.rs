@@ -7,6 +7,13 @@ pub enum Token {
     Symbol(String),
     LParen,
     RParen,
+    Interpolated(Vec<StringPart>), // f"x = {x}"
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StringPart {
+    Text(String),
+    Expr(Vec<Token>, String), // tokens of the placeholder expression and its format spec
 }
 
 pub fn tokenize(input: &str) -> Result<Vec<Token>, Error> {
@@ -36,6 +43,10 @@ pub fn tokenize(input: &str) -> Result<Vec<Token>, Error> {
 
                 tokens.push(Token::String(word));
             }
+            'f' if chars.first() == Some(&'"') => {
+                chars.remove(0);
+                tokens.push(Token::Interpolated(tokenize_interpolated(&mut chars)?));
+            }
             ';' => {
                 while !chars.is_empty() && chars[0] != '\n' {
                     chars.remove(0);
@@ -70,3 +81,83 @@ pub fn tokenize(input: &str) -> Result<Vec<Token>, Error> {
 
     Ok(tokens)
 }
+
+// body of an f-string up to its closing quote, {expr} or {expr:spec} placeholders may contain
+// string literals, {{ and }} are literal braces
+fn tokenize_interpolated(chars: &mut Vec<char>) -> Result<Vec<StringPart>, Error> {
+    let mut parts = vec![];
+    let mut text = String::new();
+
+    loop {
+        if chars.is_empty() {
+            return Err(Error::Token("suspended string definition".to_string()));
+        }
+
+        match chars.remove(0) {
+            '"' => break,
+            '{' if chars.first() == Some(&'{') => {
+                chars.remove(0);
+                text.push('{');
+            }
+            '}' if chars.first() == Some(&'}') => {
+                chars.remove(0);
+                text.push('}');
+            }
+            '}' => {
+                return Err(Error::Token(
+                    "'{' before '}' in interpolated string".to_string(),
+                ))
+            }
+            '{' => {
+                if !text.is_empty() {
+                    parts.push(StringPart::Text(std::mem::take(&mut text)));
+                }
+
+                let mut source = String::new();
+                let mut spec: Option<String> = None;
+                let mut depth = 0;
+                let mut in_string = false;
+                loop {
+                    if chars.is_empty() {
+                        return Err(Error::Token(
+                            "'}' closing interpolated expression".to_string(),
+                        ));
+                    }
+
+                    let ch = chars.remove(0);
+                    match (ch, &mut spec) {
+                        (ch, Some(spec)) if ch != '}' => spec.push(ch),
+                        ('"', None) => {
+                            in_string = !in_string;
+                            source.push(ch);
+                        }
+                        (ch, None) if in_string => source.push(ch),
+                        ('(', None) => {
+                            depth += 1;
+                            source.push(ch);
+                        }
+                        (')', None) => {
+                            depth -= 1;
+                            source.push(ch);
+                        }
+                        (':', None) if depth == 0 => spec = Some(String::new()),
+                        ('}', _) if depth == 0 => break,
+                        (ch, _) => source.push(ch),
+                    }
+                }
+
+                parts.push(StringPart::Expr(
+                    tokenize(&source)?,
+                    spec.unwrap_or_default(),
+                ));
+            }
+            ch => text.push(ch),
+        }
+    }
+
+    if !text.is_empty() {
+        parts.push(StringPart::Text(text));
+    }
+
+    Ok(parts)
+}
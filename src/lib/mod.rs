@@ -1,40 +1,91 @@
 use std::{cell::RefCell, fs, rc::Rc};
 
-use crate::{environment::Environment, eval::Evaluator, parser::parse};
+use crate::{
+    environment::Environment,
+    error::Error,
+    eval::Evaluator,
+    expression::Expression,
+    parser::parse,
+    transform::{expand, transform_program_to_block},
+};
 
+pub mod builtins;
+pub mod cst;
 pub mod environment;
 pub mod error;
 pub mod eval;
 pub mod expression;
+pub mod formatter;
 pub mod lexer;
 pub mod parser;
 pub mod repl;
+pub mod stdlib;
+pub mod testing;
 pub mod transform;
 
-pub fn run_code(path: String) {
-    let mut eval = Evaluator::default();
-    let mut env = Rc::new(RefCell::new(Environment::new()));
-
+// runs a program file and returns the process exit status, errors are reported on stderr
+pub fn run_code(path: String, search_paths: Vec<String>, argv: Vec<String>) -> i32 {
     // current working file path
-    if let Some((_, cwf)) = path
-        .split('/')
-        .map(|s| s.to_string())
-        .collect::<Vec<String>>()
-        .split_last()
-    {
-        eval.set_cwf_path(cwf.join("/"));
-    }
+    let cwf_path = match path.rsplit_once('/') {
+        Some((dir, _)) => dir.to_string(),
+        None => String::new(),
+    };
 
     match fs::read_to_string(path) {
-        Ok(ctx) => {
-            match parse(&ctx) {
-                Ok(exp) => match eval.eval_exp(&exp, &mut env) {
-                    Ok(_) => (),
-                    Err(err) => println!("{}", err),
-                },
-                Err(err) => println!("{}", err),
-            };
+        Ok(source) => run_source(&source, cwf_path, search_paths, argv, false),
+        Err(err) => {
+            eprintln!("{}", Error::from(err));
+            1
+        }
+    }
+}
+
+// runs program text, imports are resolved from cwf_path, the value of the program is
+// printed unless it is nil when print_result is set
+pub fn run_source(
+    source: &str,
+    cwf_path: String,
+    search_paths: Vec<String>,
+    argv: Vec<String>,
+    print_result: bool,
+) -> i32 {
+    let mut eval = evaluator(cwf_path, search_paths, argv);
+    let res = eval_source(&mut eval, source).and_then(|value| {
+        if print_result && value != Expression::Void {
+            println!("{}", eval.stringify(&value)?);
+        }
+        Ok(())
+    });
+
+    match res {
+        Ok(_) => 0,
+        Err(Error::Exit(code)) => code,
+        Err(err) => {
+            eprintln!("{}", err);
+            1
         }
-        Err(err) => println!("{}", err),
     }
 }
+
+// evaluator for a program file, imports are resolved from cwf_path then search_paths
+pub fn evaluator(cwf_path: String, search_paths: Vec<String>, argv: Vec<String>) -> Evaluator {
+    let mut eval = Evaluator::default();
+    for search_path in search_paths {
+        eval.add_search_path(search_path);
+    }
+    eval.set_argv(argv);
+    eval.set_cwf_path(cwf_path);
+    eval
+}
+
+// evaluates program text in a fresh global environment
+pub fn eval_source(eval: &mut Evaluator, source: &str) -> Result<Expression, Error> {
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    eval.eval_program(&parse(source)?, &mut env)
+}
+
+// parses program text and expands its syntax without evaluating it
+pub fn check_source(source: &str) -> Result<(), Error> {
+    let program = transform_program_to_block(&parse(source)?)?;
+    expand(&program).map(|_| ())
+}
@@ -3,7 +3,7 @@ use std::collections::VecDeque;
 use crate::{
     error::Error,
     expression::Expression,
-    lexer::{tokenize, Token},
+    lexer::{tokenize, StringPart, Token},
 };
 
 pub fn parse(program: &str) -> Result<Expression, Error> {
@@ -29,6 +29,7 @@ fn parse_tokens(
                 Token::Number(num) => res.push(Expression::Number(num)),
                 Token::String(s) => res.push(Expression::String(s)),
                 Token::Symbol(k) => res.push(Expression::Symbol(k)),
+                Token::Interpolated(parts) => res.push(parse_interpolated(parts)?),
                 Token::LParen => {
                     paren_stack.push_back(());
                     res.push(parse_tokens(tokens, paren_stack)?)
@@ -49,3 +50,40 @@ fn parse_tokens(
 
     Ok(Expression::List(res))
 }
+
+// f"x = {x:.2}" becomes a call of the format built-in, (format "x = {:.2}" x)
+fn parse_interpolated(parts: Vec<StringPart>) -> Result<Expression, Error> {
+    let mut template = String::new();
+    let mut args = vec![];
+
+    for part in parts {
+        match part {
+            StringPart::Text(text) => template += &text.replace('{', "{{").replace('}', "}}"),
+            StringPart::Expr(mut tokens, spec) => {
+                tokens.reverse();
+                let Expression::List(mut forms) = parse_tokens(&mut tokens, &mut VecDeque::new())?
+                else {
+                    unreachable!()
+                };
+                if forms.len() != 1 {
+                    return Err(Error::Parse(
+                        "interpolated placeholder must contain one expression".to_string(),
+                    ));
+                }
+
+                template += &match spec.is_empty() {
+                    true => "{}".to_string(),
+                    false => format!("{{:{}}}", spec),
+                };
+                args.push(forms.remove(0));
+            }
+        }
+    }
+
+    let mut call = vec![
+        Expression::Native("format".to_string()),
+        Expression::String(template),
+    ];
+    call.extend(args);
+    Ok(Expression::List(call))
+}
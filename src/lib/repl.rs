@@ -1,4 +1,4 @@
-use std::{cell::RefCell, io::stdin, rc::Rc};
+use std::{cell::RefCell, io::stdin, process, rc::Rc};
 
 use linefeed::{Interface, ReadResult};
 
@@ -20,7 +20,11 @@ pub fn repl() {
         }
 
         match evaluate_input(&mut input, &mut eval, &mut global_env) {
-            Ok(val) => println!("{}", val),
+            Ok(val) => match eval.stringify(&val) {
+                Ok(val) => println!("{}", val),
+                Err(err) => println!("{}", err),
+            },
+            Err(Error::Exit(code)) => process::exit(code),
             Err(err) => println!("{}", err),
         };
     }
@@ -34,7 +38,7 @@ fn evaluate_input(
     env: &mut Rc<RefCell<Environment>>,
 ) -> Result<Expression, Error> {
     match parse(input) {
-        Ok(exp) => eval.eval_exp(&exp, env),
+        Ok(exp) => eval.eval_program(&exp, env),
         Err(err) => match err {
             Error::Parse(err_desc) if err_desc == *"could not find closing ')'" => {
                 let mut buf = String::new();
@@ -0,0 +1,17 @@
+// ema sources of the bundled std modules, their natives come from builtins
+pub fn source(name: &str) -> Option<&'static str> {
+    match name {
+        "fs" => Some(include_str!("std/fs.ema")),
+        "io" => Some(include_str!("std/io.ema")),
+        "json" => Some(include_str!("std/json.ema")),
+        "lists" => Some(include_str!("std/lists.ema")),
+        "maps" => Some(include_str!("std/maps.ema")),
+        "math" => Some(include_str!("std/math.ema")),
+        "random" => Some(include_str!("std/random.ema")),
+        "regex" => Some(include_str!("std/regex.ema")),
+        "strings" => Some(include_str!("std/strings.ema")),
+        "test" => Some(include_str!("std/test.ema")),
+        "time" => Some(include_str!("std/time.ema")),
+        _ => None,
+    }
+}
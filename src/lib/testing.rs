@@ -0,0 +1,154 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    error::Error,
+    eval::{Evaluator, TestCase},
+    eval_source, evaluator,
+};
+
+// outcome of one deftest, a file which fails to load is reported as a single failure
+#[derive(Debug)]
+pub struct TestResult {
+    pub name: String,
+    pub error: Option<Error>,
+}
+
+// test files under the given paths, directories are searched recursively for files
+// ending with _test.ema, files given explicitly are always included
+pub fn discover(paths: &[String]) -> Result<Vec<PathBuf>, Error> {
+    let mut files = vec![];
+    for path in paths {
+        let path = Path::new(path);
+        if path.is_dir() {
+            collect_test_files(path, &mut files)?;
+        } else if path.is_file() {
+            files.push(path.to_path_buf());
+        } else {
+            return Err(Error::Io(format!(
+                "cannot find test path {}",
+                path.display()
+            )));
+        }
+    }
+
+    Ok(files)
+}
+
+fn collect_test_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), Error> {
+    let mut entries = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<PathBuf>, _>>()?;
+    entries.sort();
+
+    for entry in entries {
+        if entry.is_dir() {
+            collect_test_files(&entry, files)?;
+        } else if entry
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().ends_with("_test.ema"))
+        {
+            files.push(entry);
+        }
+    }
+
+    Ok(())
+}
+
+// evaluates a file then each test it declares, every test gets the file evaluated again
+// by a fresh evaluator so nothing a test changes in the file scope or the module cache
+// reaches the other tests
+pub fn run_file(path: &Path, search_paths: &[String]) -> Vec<TestResult> {
+    let cwf_path = path
+        .parent()
+        .map(|dir| dir.display().to_string())
+        .unwrap_or_default();
+    let load = |source: &str| -> Result<(Evaluator, Vec<TestCase>), Error> {
+        let mut eval = evaluator(cwf_path.clone(), search_paths.to_vec(), vec![]);
+        eval_source(&mut eval, source)?;
+        let tests = eval.take_tests();
+        Ok((eval, tests))
+    };
+
+    let loaded = fs::read_to_string(path)
+        .map_err(Error::from)
+        .and_then(|source| load(&source).map(|first| (source, first)));
+    let (source, first) = match loaded {
+        Ok(loaded) => loaded,
+        Err(err) => {
+            return vec![TestResult {
+                name: "(loading file)".to_string(),
+                error: Some(err),
+            }]
+        }
+    };
+
+    let names = first
+        .1
+        .iter()
+        .map(|test| test.name.clone())
+        .collect::<Vec<String>>();
+    let mut first = Some(first);
+    names
+        .into_iter()
+        .enumerate()
+        .map(|(idx, name)| {
+            let error = first
+                .take()
+                .map_or_else(|| load(&source), Ok)
+                .and_then(|(mut eval, tests)| match tests.get(idx) {
+                    Some(test) => eval.run_test(test),
+                    None => Err(Error::Reason(format!("test {} disappeared", name))),
+                })
+                .err();
+            TestResult { name, error }
+        })
+        .collect()
+}
+
+// runs the tests under paths, prints a report and returns the process exit status
+pub fn run_tests(paths: Vec<String>, search_paths: Vec<String>) -> i32 {
+    let files = match discover(&paths) {
+        Ok(files) => files,
+        Err(err) => {
+            eprintln!("{}", err);
+            return 2;
+        }
+    };
+
+    let (mut passed, mut failed) = (0, 0);
+    for file in files {
+        println!("{}", file.display());
+        for result in run_file(&file, &search_paths) {
+            match result.error {
+                None => {
+                    passed += 1;
+                    println!("  ok    {}", result.name);
+                }
+                Some(err) => {
+                    failed += 1;
+                    println!("  FAIL  {}", result.name);
+                    for line in failure_message(&err).lines() {
+                        println!("        {}", line);
+                    }
+                }
+            }
+        }
+    }
+
+    println!("\n{} passed, {} failed", passed, failed);
+    match failed {
+        0 => 0,
+        _ => 1,
+    }
+}
+
+fn failure_message(err: &Error) -> String {
+    match err {
+        Error::Assertion(message) => message.clone(),
+        Error::Exit(code) => format!("test exited with status {}", code),
+        err => format!("{} error: {}", err.kind(), err.message()),
+    }
+}
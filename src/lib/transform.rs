@@ -13,6 +13,22 @@ pub fn transform_module_to_class(list: &[Expression]) -> Result<Expression, Erro
     ]))
 }
 
+// top-level forms of a file form an implicit block
+pub fn transform_program_to_block(program: &Expression) -> Result<Expression, Error> {
+    let Expression::List(forms) = program else {
+        return Err(Error::Invalid("invalid program".to_string()));
+    };
+
+    match &forms[..] {
+        [form] => Ok(form.clone()),
+        _ => {
+            let mut block = vec![Expression::Symbol("begin".to_string())];
+            block.extend(forms.iter().cloned());
+            Ok(Expression::List(block))
+        }
+    }
+}
+
 pub fn transform_def_to_var_lambda(list: &[Expression]) -> Result<Expression, Error> {
     let [_tag, name, params, body] = &list else {
         return Err(Error::Invalid("invalid defining function.".to_string()))
@@ -154,3 +170,34 @@ pub fn transform_compound_assign(list: &[Expression]) -> Result<Expression, Erro
         Expression::List(vec![op, left.clone(), right.clone()]),
     ]))
 }
+
+// applies the transformations the evaluator performs when it reaches a form to the whole
+// tree, quoted forms are left as they are
+pub fn expand(exp: &Expression) -> Result<Expression, Error> {
+    let Expression::List(list) = exp else {
+        return Ok(exp.clone());
+    };
+
+    let transformed = match list.first() {
+        Some(Expression::Symbol(head)) => match head.as_str() {
+            "quote" => return Ok(exp.clone()),
+            "def" => Some(transform_def_to_var_lambda(list)?),
+            "switch" => Some(transform_switch_to_if(list)?),
+            "for" => Some(transform_for_to_while(list)?),
+            "++" | "--" => Some(transform_incdec(list)?),
+            "+=" | "-=" | "*=" | "/=" | "%=" => Some(transform_compound_assign(list)?),
+            "module" | "mixin" => Some(transform_module_to_class(list)?),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    match transformed {
+        Some(exp) => expand(&exp),
+        None => Ok(Expression::List(
+            list.iter()
+                .map(expand)
+                .collect::<Result<Vec<Expression>, Error>>()?,
+        )),
+    }
+}
@@ -1,6 +1,8 @@
 use std::{cell::RefCell, rc::Rc};
 
-use ema::{environment::Environment, eval::Evaluator, expression::Expression, parser::parse};
+use ema::{
+    environment::Environment, error::Error, eval::Evaluator, expression::Expression, parser::parse,
+};
 
 #[test]
 fn define_and_use_class() {
@@ -94,3 +96,394 @@ fn inheritance() {
         Ok(Expression::Number(60.0))
     );
 }
+
+#[test]
+fn method_call_binds_self() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let exp = parse(
+        "
+    (begin
+        (class Point nil
+            (begin
+                (def constructor (self x y)
+                    (begin
+                        (set (prop self x) x)
+                        (set (prop self y) y)
+                    )
+                )
+
+                (def calc (self)
+                    (+ (prop self x) (prop self y))
+                )
+
+                (def scale (self factor)
+                    (* (. self calc) factor)
+                )
+            )
+        )
+
+        (class Point3D Point
+            (begin
+                (def constructor (self x y z)
+                    (begin
+                        (. (super Point3D) constructor x y)
+                        (set (prop self z) z)
+                    )
+                )
+
+                (def calc (self)
+                    (+ (. (super Point3D) calc) (prop self z))
+                )
+            )
+        )
+
+        (var p (new Point3D 10 20 30))
+        (. p scale 2)
+    )",
+    );
+
+    assert_eq!(
+        eval.eval_exp(&exp.unwrap(), &mut env),
+        Ok(Expression::Number(120.0))
+    );
+}
+
+#[test]
+fn instances_keep_their_own_state() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let exp = parse(
+        "
+    (begin
+        (class Counter nil
+            (begin
+                (def constructor (self start)
+                    (begin
+                        (set (prop self count) start)
+                        (set (prop self me) self)
+                    )
+                )
+
+                (def inc (self)
+                    (set (prop self count) (+ (prop self count) 1))
+                )
+            )
+        )
+
+        (var a (new Counter 10))
+        (var b (new Counter 100))
+        (. a inc)
+        (. a inc)
+        (. (prop b me) inc)
+        (list (prop a count) (prop b count) (prop (prop a me) count))
+    )",
+    );
+
+    assert_eq!(
+        eval.eval_exp(&exp.unwrap(), &mut env),
+        Ok(Expression::List(vec![
+            Expression::Number(12.0),
+            Expression::Number(101.0),
+            Expression::Number(12.0),
+        ]))
+    );
+}
+
+#[test]
+fn super_of_class_and_instance() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let exp = parse(
+        "
+    (begin
+        (class A nil
+            (begin
+                (def name (self) \"A\")
+            )
+        )
+
+        (class B A
+            (begin
+                (def name (self) \"B\")
+            )
+        )
+
+        (class C B
+            (begin
+                (def name (self) \"C\")
+            )
+        )
+
+        (var c (new C))
+        (list
+            (. c name)
+            ((prop (super c) name) c)
+            ((prop (super C) name) c)
+            ((prop (super B) name) c)
+        )
+    )",
+    );
+
+    assert_eq!(
+        eval.eval_exp(&exp.unwrap(), &mut env),
+        Ok(Expression::List(vec![
+            Expression::String("C".to_string()),
+            Expression::String("B".to_string()),
+            Expression::String("B".to_string()),
+            Expression::String("A".to_string()),
+        ]))
+    );
+}
+
+#[test]
+fn static_members() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let exp = parse(
+        "
+    (begin
+        (class Counter nil
+            (begin
+                (static var created 0)
+                (var count 0)
+
+                (static def describe () \"counter\")
+
+                (def constructor (self)
+                    (set (prop self created) (+ (prop self created) 1))
+                )
+
+                (def inc (self)
+                    (set (prop self count) (+ (prop self count) 1))
+                )
+            )
+        )
+
+        (var a (new Counter))
+        (var b (new Counter))
+        (. a inc)
+        (. a inc)
+        (. b inc)
+        (list (prop Counter created) (prop a count) (prop b count) ((prop Counter describe)) (fields a))
+    )",
+    );
+
+    assert_eq!(
+        eval.eval_exp(&exp.unwrap(), &mut env),
+        Ok(Expression::List(vec![
+            Expression::Number(2.0),
+            Expression::Number(2.0),
+            Expression::Number(1.0),
+            Expression::String("counter".to_string()),
+            Expression::List(vec![Expression::String("count".to_string())]),
+        ]))
+    );
+}
+
+#[test]
+fn getters_and_setters() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let exp = parse(
+        "
+    (begin
+        (class Temperature nil
+            (begin
+                (def constructor (self celsius)
+                    (set (prop self celsius) celsius)
+                )
+
+                (get fahrenheit (self)
+                    (+ (* (prop self celsius) 1.8) 32)
+                )
+
+                (set fahrenheit (self value)
+                    (set (prop self celsius) (/ (- value 32) 1.8))
+                )
+
+                (get kelvin (self)
+                    (+ (prop self celsius) 273)
+                )
+            )
+        )
+
+        (var t (new Temperature 100))
+        (var before (prop t fahrenheit))
+        (set (prop t fahrenheit) 32)
+        (list before (prop t celsius) (prop t kelvin))
+    )",
+    );
+
+    assert_eq!(
+        eval.eval_exp(&exp.unwrap(), &mut env),
+        Ok(Expression::List(vec![
+            Expression::Number(212.0),
+            Expression::Number(0.0),
+            Expression::Number(273.0),
+        ]))
+    );
+
+    let exp = parse(
+        "
+    (begin
+        (class Circle nil
+            (get area (self) 3)
+        )
+        (set (prop (new Circle) area) 10)
+    )",
+    );
+
+    assert!(eval.eval_exp(&exp.unwrap(), &mut env).is_err());
+
+    // outside of class bodies get is an ordinary name
+    let exp = parse("(begin (def get (m k) (+ m k)) (get 1 2))");
+    assert_eq!(
+        eval.eval_exp(&exp.unwrap(), &mut env),
+        Ok(Expression::Number(3.0))
+    );
+}
+
+#[test]
+fn private_fields() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let classes = "
+        (class Account nil
+            (begin
+                (def constructor (self owner)
+                    (begin
+                        (set (prop self owner) owner)
+                        (set (prop self #balance) 0)
+                    )
+                )
+
+                (def deposit (self amount)
+                    (set (prop self #balance) (+ (prop self #balance) amount))
+                )
+
+                (def balance (self) (prop self #balance))
+            )
+        )
+
+        (class Savings Account
+            (def peek (self) (prop self #balance))
+        )
+
+        (var acc (new Savings \"ema\"))
+        (. acc deposit 50)
+    ";
+
+    let exp = parse(&format!("(begin {} (. acc balance))", classes));
+    assert_eq!(
+        eval.eval_exp(&exp.unwrap(), &mut env),
+        Ok(Expression::Number(50.0))
+    );
+
+    for access in [
+        "(prop acc #balance)",
+        "(set (prop acc #balance) 100)",
+        "(. acc peek)",
+    ] {
+        let exp = parse(&format!("(begin {} {})", classes, access));
+        assert!(eval.eval_exp(&exp.unwrap(), &mut env).is_err());
+    }
+}
+
+#[test]
+fn protocols() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let shapes = "
+        (protocol Shape (area perimeter))
+
+        (class Square nil (implements Shape)
+            (begin
+                (def constructor (self side) (set (prop self side) side))
+                (def area (self) (* (prop self side) (prop self side)))
+                (def perimeter (self) (* (prop self side) 4))
+            )
+        )
+
+        (class Tile Square
+            (def color (self) \"red\")
+        )
+    ";
+
+    let exp = parse(&format!(
+        "(begin {} (var t (new Tile 3)) (list (. t area) (implements? t Shape) (implements? 1 Shape)))",
+        shapes
+    ));
+    assert_eq!(
+        eval.eval_exp(&exp.unwrap(), &mut env),
+        Ok(Expression::List(vec![
+            Expression::Number(9.0),
+            Expression::Boolean(true),
+            Expression::Boolean(false),
+        ]))
+    );
+
+    let exp = parse(&format!(
+        "(begin {} (class Line nil (implements Shape) (def length (self) 1)))",
+        shapes
+    ));
+    assert_eq!(
+        eval.eval_exp(&exp.unwrap(), &mut env),
+        Err(Error::Invalid(
+            "class Line does not implement protocol Shape, missing methods: area, perimeter"
+                .to_string()
+        ))
+    );
+}
+
+#[test]
+fn mixins() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let exp = parse(
+        "
+    (begin
+        (mixin Named
+            (begin
+                (def describe (self) \"named\")
+                (def greet (self) (+ \"hello \" (prop self name)))
+            )
+        )
+
+        (mixin Loud
+            (begin
+                (def describe (self) \"loud\")
+                (def shout (self) \"HEY\")
+            )
+        )
+
+        (class Base nil
+            (begin
+                (def describe (self) \"base\")
+                (def shout (self) \"base shout\")
+                (def base_only (self) \"base only\")
+            )
+        )
+
+        (class Person Base (with Named Loud)
+            (begin
+                (def constructor (self name) (set (prop self name) name))
+                (def shout (self) \"person shout\")
+            )
+        )
+
+        (var p (new Person \"ema\"))
+        (list (. p greet) (. p describe) (. p shout) (. p base_only))
+    )",
+    );
+
+    assert_eq!(
+        eval.eval_exp(&exp.unwrap(), &mut env),
+        Ok(Expression::List(vec![
+            Expression::String("hello ema".to_string()),
+            Expression::String("named".to_string()),
+            Expression::String("person shout".to_string()),
+            Expression::String("base only".to_string()),
+        ]))
+    );
+}
@@ -0,0 +1,110 @@
+use std::{
+    io::Write,
+    process::{Command, Output, Stdio},
+};
+
+fn ema(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_ema"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+#[test]
+fn run_files_inline_code_and_stdin() {
+    let output = ema(&["tests/scripts/exit_code.ema", "a", "-e", "c"], "");
+    assert_eq!(output.status.code(), Some(3));
+
+    let output = ema(&["run", "-e", "(list (+ 1 2) argv)", "x", "--flag"], "");
+    assert_eq!(stdout(&output), "(3 (x --flag))\n");
+
+    let output = ema(&["-", "arg"], "(print \"from stdin \" argv)");
+    assert_eq!(stdout(&output), "from stdin (arg)\n");
+    assert_eq!(output.status.code(), Some(0));
+
+    let output = ema(&["run", "tests/scripts/failing.ema"], "");
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn check_and_ast() {
+    let output = ema(&["check", "tests/scripts/exit_code.ema", "-"], "(def f (x)");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "tests/scripts/exit_code.ema: ok\n");
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("-: parsing error"));
+
+    let output = ema(&["ast", "-e", "(++ x) \"s\""], "");
+    assert_eq!(
+        stdout(&output),
+        "List\n  Symbol ++\n  Symbol x\nString \"s\"\n"
+    );
+
+    let output = ema(&["ast", "--expanded", "-e", "(++ x)"], "");
+    assert_eq!(
+        stdout(&output),
+        "List\n  Symbol set\n  Symbol x\n  List\n    Symbol +\n    Symbol x\n    Number 1\n"
+    );
+}
+
+#[test]
+fn help_version_and_usage_errors() {
+    let output = ema(&["--version"], "");
+    assert_eq!(
+        stdout(&output),
+        format!("ema {}\n", env!("CARGO_PKG_VERSION"))
+    );
+
+    let output = ema(&["test", "--help"], "");
+    assert!(stdout(&output).starts_with("usage: ema [command]"));
+    assert_eq!(output.status.code(), Some(0));
+
+    let output = ema(&["check", "--expanded"], "");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .starts_with("unknown option --expanded for ema check"));
+}
+
+#[test]
+fn fmt_files_and_check_mode() {
+    let dir = std::env::temp_dir().join(format!("ema_fmt_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let messy = dir.join("messy.ema");
+    let tidy = dir.join("tidy.ema");
+    std::fs::write(&messy, "(begin   (print 1) ; one\n)").unwrap();
+    std::fs::write(&tidy, "(print 1)\n").unwrap();
+    let (messy, tidy) = (messy.to_str().unwrap(), tidy.to_str().unwrap());
+
+    let output = ema(&["fmt", "--check", messy, tidy], "");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), format!("{}\n", messy));
+
+    let output = ema(&["fmt", messy], "");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        std::fs::read_to_string(messy).unwrap(),
+        "(begin\n  (print 1) ; one\n)\n"
+    );
+    assert_eq!(
+        ema(&["fmt", "--check", messy, tidy], "").status.code(),
+        Some(0)
+    );
+
+    let output = ema(&["fmt", "--width", "12", "-"], "(print \"a\" \"b\")");
+    assert_eq!(stdout(&output), "(print\n  \"a\"\n  \"b\")\n");
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
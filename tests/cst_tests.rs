@@ -0,0 +1,83 @@
+use std::fs;
+
+use ema::{
+    cst::{parse_cst, Span, TokenKind, TriviaKind},
+    error::Error,
+    parser::parse,
+};
+
+#[test]
+fn cst_prints_source_back() {
+    let sources = [
+        "",
+        "  \n; only a comment",
+        "; header\r\n(var x   10) ; ten\r\n\r\n\r\n(print  f\"{x} is {(+ x \"1\"):>5} {{ok}}\")  \n",
+        "(begin\n\t(print \"multi\nline\")   ; trailing\n\n  ; own line\n  )\n;; end",
+    ];
+
+    for source in sources {
+        let tree = parse_cst(source).unwrap();
+        assert_eq!(tree.to_string(), source);
+        assert_eq!(tree.to_expression(), parse(source));
+    }
+
+    for entry in fs::read_dir("ema_example").unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_some_and(|ext| ext == "ema") {
+            let source = fs::read_to_string(&path).unwrap();
+            let tree = parse_cst(&source).unwrap();
+            assert_eq!(tree.to_string(), source, "{}", path.display());
+            assert_eq!(tree.to_expression(), parse(&source), "{}", path.display());
+        }
+    }
+}
+
+#[test]
+fn cst_attaches_trivia_to_tokens() {
+    let source = "; lead\n(add 1 \"two\") ; tail\n\n";
+    let tree = parse_cst(source).unwrap();
+    let tokens = tree.tokens();
+
+    let kinds = tokens.iter().map(|token| token.kind).collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        vec![
+            TokenKind::LParen,
+            TokenKind::Symbol,
+            TokenKind::Number,
+            TokenKind::String,
+            TokenKind::RParen,
+            TokenKind::Eof,
+        ]
+    );
+
+    // the comment before the list leads its paren, the one after it trails the close paren
+    let open = tokens[0];
+    assert_eq!(
+        open.leading.iter().map(|t| t.kind).collect::<Vec<_>>(),
+        vec![TriviaKind::Comment, TriviaKind::Newline]
+    );
+    let close = tokens[4];
+    assert_eq!(close.trailing[1].text, "; tail");
+    assert_eq!(tokens[5].leading.len(), 2);
+
+    assert_eq!(tree.items[0].span(), Span { start: 7, end: 20 });
+    assert_eq!(tree.token_at(14).map(|t| t.text.as_str()), Some("\"two\""));
+    assert_eq!(tree.token_at(2), None);
+}
+
+#[test]
+fn cst_reports_parse_errors() {
+    assert_eq!(
+        parse_cst("(print 1"),
+        Err(Error::Parse("could not find closing ')'".to_string()))
+    );
+    assert_eq!(
+        parse_cst("(print 1))"),
+        Err(Error::Parse("unexpected ')'".to_string()))
+    );
+    assert_eq!(
+        parse_cst("(print \"open)"),
+        Err(Error::Token("suspended string definition".to_string()))
+    );
+}
@@ -0,0 +1,84 @@
+use std::{cell::RefCell, rc::Rc};
+
+use ema::{
+    environment::Environment, error::Error, eval::Evaluator, expression::Expression, parser::parse,
+};
+
+#[test]
+fn format_placeholders_and_specs() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let exp = parse(
+        "
+    (list
+        (format \"{} + {} = {}\" 1 2 3)
+        (format \"{1} {0} {{literal}}\" \"world\" \"hello\")
+        (format \"{:.2}|{:>6.1}|{:<5}|{:^7}|{:*^7}\" 3.14159 2.25 \"ab\" \"mid\" \"x\")
+        (format \"{:+}|{:05}|{:+06.1}|{:.3}\" 5 42 -1.25 \"truncate\")
+        (format \"{:?} {:?} {}\" \"quoted\" (list 1 \"a\") (list 1 \"a\"))
+    )",
+    );
+
+    assert_eq!(
+        eval.eval_exp(&exp.unwrap(), &mut env),
+        Ok(Expression::List(vec![
+            Expression::String("1 + 2 = 3".to_string()),
+            Expression::String("hello world {literal}".to_string()),
+            Expression::String("3.14|   2.2|ab   |  mid  |***x***".to_string()),
+            Expression::String("+5|00042|-001.2|tru".to_string()),
+            Expression::String("\"quoted\" (1 \"a\") (1 a)".to_string()),
+        ]))
+    );
+}
+
+#[test]
+fn interpolated_strings() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let exp = parse(
+        "
+    (begin
+        (import upper std.strings)
+        (var name \"ema\")
+        (var price 9.5)
+        (def greet (who) f\"hi {who}\")
+        (list
+            f\"{name} costs {price:.2} {{braces}}\"
+            f\"{(upper name)}: {(+ 1 2)} {(greet \"you\")}\"
+            f\"plain\"
+        )
+    )",
+    );
+
+    assert_eq!(
+        eval.eval_exp(&exp.unwrap(), &mut env),
+        Ok(Expression::List(vec![
+            Expression::String("ema costs 9.50 {braces}".to_string()),
+            Expression::String("EMA: 3 hi you".to_string()),
+            Expression::String("plain".to_string()),
+        ]))
+    );
+}
+
+#[test]
+fn format_errors() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+
+    assert_eq!(
+        eval.eval_exp(&parse("(format \"{} {}\" 1)").unwrap(), &mut env),
+        Err(Error::Invalid(
+            "format placeholder 1 has no argument, 1 given".to_string()
+        ))
+    );
+    assert_eq!(
+        eval.eval_exp(&parse("(format \"{:x}\" 1)").unwrap(), &mut env),
+        Err(Error::Invalid("invalid format spec x".to_string()))
+    );
+    assert_eq!(
+        parse("f\"{name\""),
+        Err(Error::Token(
+            "'}' closing interpolated expression".to_string()
+        ))
+    );
+}
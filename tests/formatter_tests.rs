@@ -0,0 +1,73 @@
+use ema::{
+    error::Error,
+    formatter::{format_source, DEFAULT_WIDTH},
+};
+
+#[test]
+fn canonical_layout_keeps_comments() {
+    let source = "; greeting program
+(begin   (var name    \"ema\")   ; who to greet
+
+
+    (def greet (who) (begin (print f\"hello {who}\") (print \"a long line which does not fit on one line\")))
+  (greet name)
+  ; done
+)
+";
+
+    assert_eq!(
+        format_source(source, DEFAULT_WIDTH),
+        Ok("; greeting program
+(begin
+  (var name \"ema\") ; who to greet
+
+  (def greet (who)
+    (begin
+      (print f\"hello {who}\")
+      (print \"a long line which does not fit on one line\")))
+  (greet name)
+  ; done
+)
+"
+        .to_string())
+    );
+}
+
+#[test]
+fn width_is_configurable_and_output_is_stable() {
+    let source = "(var res_if (if (> n 5) \"greater than 5\" (if (< n 5) \"less\" \"equal\")))";
+
+    let wide = format_source(source, 100).unwrap();
+    assert_eq!(wide, format!("{}\n", source));
+
+    let narrow = format_source(source, 34).unwrap();
+    assert_eq!(
+        narrow,
+        "(var res_if
+  (if (> n 5)
+    \"greater than 5\"
+    (if (< n 5) \"less\" \"equal\")))
+"
+    );
+    assert_eq!(format_source(&narrow, 34), Ok(narrow.clone()));
+    assert_eq!(format_source(&narrow, 100), Ok(wide));
+}
+
+#[test]
+fn invalid_programs_are_not_formatted() {
+    assert_eq!(
+        format_source("(begin (print 1)", DEFAULT_WIDTH),
+        Err(Error::Parse("could not find closing ')'".to_string()))
+    );
+    assert_eq!(
+        format_source("(print \"unterminated)", DEFAULT_WIDTH),
+        Err(Error::Token("suspended string definition".to_string()))
+    );
+    assert_eq!(format_source("  \n\n", DEFAULT_WIDTH), Ok(String::new()));
+
+    // nan never equals itself but does not change the meaning of the program
+    assert_eq!(
+        format_source("(var x   nan)", DEFAULT_WIDTH),
+        Ok("(var x nan)\n".to_string())
+    );
+}
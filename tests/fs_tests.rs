@@ -0,0 +1,103 @@
+use std::{cell::RefCell, env, rc::Rc};
+
+use ema::{
+    environment::Environment, error::Error, eval::Evaluator, expression::Expression, parser::parse,
+};
+
+// scratch directory for one test, removed by the ema code itself
+fn scratch_dir(name: &str) -> String {
+    env::temp_dir()
+        .join(format!("ema-{}-{}", name, std::process::id()))
+        .display()
+        .to_string()
+}
+
+#[test]
+fn write_append_and_read_files() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let dir = scratch_dir("files");
+    let exp = parse(&format!(
+        "
+    (begin
+        (import * std.fs)
+        (var dir \"{}\")
+        (var path (+ dir \"/notes.txt\"))
+        (mkdir dir)
+        (write path \"first
+\")
+        (append path \"second\")
+        (var lines 0)
+        (each-line path (lambda (line) (set lines (+ lines 1))))
+        (var res (list (read path) (read-lines path) lines (list-dir dir)))
+        (rmdir dir true)
+        (list res (exists? dir))
+    )",
+        dir
+    ));
+
+    assert_eq!(
+        eval.eval_exp(&exp.unwrap(), &mut env),
+        Ok(Expression::List(vec![
+            Expression::List(vec![
+                Expression::String("first\nsecond".to_string()),
+                Expression::List(vec![
+                    Expression::String("first".to_string()),
+                    Expression::String("second".to_string()),
+                ]),
+                Expression::Number(2.0),
+                Expression::List(vec![Expression::String("notes.txt".to_string())]),
+            ]),
+            Expression::Boolean(false),
+        ]))
+    );
+}
+
+#[test]
+fn io_errors_are_catchable() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let exp = parse(
+        "
+    (begin
+        (import (read read-or) std.fs)
+        (import lookup std.maps)
+        (list
+            (try (read \"missing.txt\") (catch err (lookup err \"kind\")))
+            (read-or \"missing.txt\" \"default\")
+            (try (throw \"boom\") (catch err (lookup err \"message\")))
+            (try
+                (try (read \"missing.txt\") (catch err (throw err)))
+                (catch err (lookup err \"kind\")))
+        )
+    )",
+    );
+
+    assert_eq!(
+        eval.eval_exp(&exp.unwrap(), &mut env),
+        Ok(Expression::List(vec![
+            Expression::String("io".to_string()),
+            Expression::String("default".to_string()),
+            Expression::String("boom".to_string()),
+            Expression::String("io".to_string()),
+        ]))
+    );
+}
+
+#[test]
+fn uncaught_io_error() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let exp = parse(
+        "
+    (begin
+        (import * std.fs)
+        (rmdir \"missing-dir\")
+    )",
+    );
+
+    match eval.eval_exp(&exp.unwrap(), &mut env) {
+        Err(Error::Io(msg)) => assert!(msg.starts_with("cannot remove directory missing-dir: ")),
+        other => panic!("expected an io error, got {:?}", other),
+    }
+}
@@ -1,6 +1,8 @@
 use std::{cell::RefCell, rc::Rc};
 
-use ema::{environment::Environment, eval::Evaluator, expression::Expression, parser::parse};
+use ema::{
+    environment::Environment, error::Error, eval::Evaluator, expression::Expression, parser::parse,
+};
 
 #[test]
 fn import() {
@@ -65,3 +67,154 @@ fn import_more_prop() {
         Ok(Expression::Number(36.0))
     );
 }
+
+#[test]
+fn import_nested_module_from_search_path() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    eval.add_search_path("tests/modules".to_string());
+
+    let exp = parse(
+        "
+    (begin
+        (import utils.strings)
+        (import (answer) utils/strings)
+        (list ((prop strings greet) \"ema\") answer)
+    )
+",
+    );
+
+    assert_eq!(
+        eval.eval_exp(&exp.unwrap(), &mut env),
+        Ok(Expression::List(vec![
+            Expression::String("hi emahi ema".to_string()),
+            Expression::Number(42.0),
+        ]))
+    );
+}
+
+#[test]
+fn import_missing_module() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let exp = parse("(import utils.strings)");
+
+    assert!(eval.eval_exp(&exp.unwrap(), &mut env).is_err());
+}
+
+#[test]
+fn import_returns_cached_module() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    eval.add_search_path("tests/modules".to_string());
+
+    let exp = parse(
+        "
+    (begin
+        (var first (import shared.counter))
+        ((prop counter inc))
+        (def read () (begin
+            (import (count) shared/counter)
+            count
+        ))
+        ((prop first inc))
+        (list (read) (= first (import shared.counter)))
+    )
+",
+    );
+
+    assert_eq!(
+        eval.eval_exp(&exp.unwrap(), &mut env),
+        Ok(Expression::List(vec![
+            Expression::Number(2.0),
+            Expression::Boolean(true),
+        ]))
+    );
+}
+
+#[test]
+fn cyclic_import() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    eval.add_search_path("tests/modules".to_string());
+
+    let exp = parse("(import cycle.a)");
+
+    assert_eq!(
+        eval.eval_exp(&exp.unwrap(), &mut env),
+        Err(Error::Import(
+            "cyclic import tests/modules/cycle/a.ema -> tests/modules/cycle/b.ema -> tests/modules/cycle/a.ema"
+                .to_string()
+        ))
+    );
+}
+
+#[test]
+fn import_exported_names_with_aliases() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    eval.add_search_path("tests/modules".to_string());
+
+    let exp = parse(
+        "
+    (begin
+        (import shared.text as T)
+        (import (shout as loud whisper) shared.text)
+        (def wild () (begin
+            (import * shared.text)
+            (shout (whisper \"hi\"))
+        ))
+        (list ((prop T shout) \"a\") (loud \"b\") (whisper \"c\") (wild))
+    )
+",
+    );
+
+    assert_eq!(
+        eval.eval_exp(&exp.unwrap(), &mut env),
+        Ok(Expression::List(vec![
+            Expression::String("a!".to_string()),
+            Expression::String("b!".to_string()),
+            Expression::String("...c".to_string()),
+            Expression::String("...hi!".to_string()),
+        ]))
+    );
+}
+
+#[test]
+fn import_non_exported_name() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    eval.add_search_path("tests/modules".to_string());
+
+    let exp = parse("(import (suffix) shared.text)");
+    assert_eq!(
+        eval.eval_exp(&exp.unwrap(), &mut env),
+        Err(Error::Import(
+            "suffix is not exported by module shared.text".to_string()
+        ))
+    );
+
+    let exp = parse("(begin (import shared.text) (prop text suffix))");
+    assert!(eval.eval_exp(&exp.unwrap(), &mut env).is_err());
+}
+
+#[test]
+fn import_multi_form_module() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    eval.add_search_path("tests/modules".to_string());
+
+    let exp = parse(
+        "
+    (begin
+        (import (area perimeter) shared.geometry)
+        (+ (area 2) (perimeter 1))
+    )
+",
+    );
+
+    assert_eq!(
+        eval.eval_exp(&exp.unwrap(), &mut env),
+        Ok(Expression::Number(18.0))
+    );
+}
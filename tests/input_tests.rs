@@ -0,0 +1,60 @@
+use std::{cell::RefCell, io::Cursor, rc::Rc};
+
+use ema::{environment::Environment, eval::Evaluator, expression::Expression, parser::parse};
+
+#[test]
+fn read_lines_from_input_source() {
+    let mut eval = Evaluator::default();
+    eval.set_input(Cursor::new("first\r\nsecond\n"));
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let exp = parse("(list (read-line) (read-line) (read-line))");
+
+    assert_eq!(
+        eval.eval_exp(&exp.unwrap(), &mut env),
+        Ok(Expression::List(vec![
+            Expression::String("first".to_string()),
+            Expression::String("second".to_string()),
+            Expression::Void,
+        ]))
+    );
+}
+
+#[test]
+fn read_all_after_a_line() {
+    let mut eval = Evaluator::default();
+    eval.set_input(Cursor::new("header\na,b\nc,d\n"));
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let exp = parse("(list (read-line) (read-all) (read-all))");
+
+    assert_eq!(
+        eval.eval_exp(&exp.unwrap(), &mut env),
+        Ok(Expression::List(vec![
+            Expression::String("header".to_string()),
+            Expression::String("a,b\nc,d\n".to_string()),
+            Expression::String("".to_string()),
+        ]))
+    );
+}
+
+#[test]
+fn each_input_line_as_a_filter() {
+    let mut eval = Evaluator::default();
+    eval.set_input(Cursor::new("apple\nbanana\ncherry"));
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let exp = parse(
+        "
+    (begin
+        (import upper std.strings)
+        (import push std.lists)
+        (var seen (list))
+        (var count (each-input-line (lambda (line) (set seen (push seen (upper line))))))
+        (list count seen)
+    )",
+    );
+
+    let res = eval.eval_exp(&exp.unwrap(), &mut env).unwrap();
+    assert_eq!(
+        eval.stringify(&res),
+        Ok("(3 (APPLE BANANA CHERRY))".to_string())
+    );
+}
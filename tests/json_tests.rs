@@ -0,0 +1,104 @@
+use std::{cell::RefCell, rc::Rc};
+
+use ema::{
+    environment::Environment, error::Error, eval::Evaluator, expression::Expression, parser::parse,
+};
+
+#[test]
+fn parse_json_text() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    env.borrow_mut()
+        .define(
+            "text",
+            Expression::String(
+                r#"{"name": "ema", "tags": ["lisp", "rust"], "stars": 1.5e1, "fork": false, "license": null, "quote": "say \"hi\" é😀"}"#
+                    .to_string(),
+            ),
+        )
+        .unwrap();
+    let exp = parse(
+        "
+    (begin
+        (import (lookup keys) std.maps)
+        (import decode std.json)
+        (var data (json-parse text))
+        (list (keys data) (lookup data \"tags\") (lookup data \"stars\") (lookup data \"license\") (lookup data \"quote\") (decode \"[1, 2]\"))
+    )",
+    );
+
+    let res = eval.eval_exp(&exp.unwrap(), &mut env).unwrap();
+    assert_eq!(
+        eval.stringify(&res),
+        Ok(
+            "((fork license name quote stars tags) (lisp rust) 15 nil say \"hi\" é😀 (1 2))"
+                .to_string()
+        )
+    );
+}
+
+#[test]
+fn stringify_values_and_instances() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let exp = parse(
+        "
+    (begin
+        (import make std.maps)
+        (class Point nil
+            (begin
+                (def constructor (self x y)
+                    (begin
+                        (set (prop self x) x)
+                        (set (prop self y) y)
+                        (set (prop self #secret) 0)
+                    )
+                )
+            )
+        )
+        (class Money nil
+            (begin
+                (def constructor (self cents) (set (prop self cents) cents))
+                (def to-json (self) (/ (prop self cents) 100))
+            )
+        )
+        (list
+            (json-stringify (make \"at\" (new Point 1 2) \"price\" (new Money 250) \"tags\" (list)))
+            (json-stringify (list 1 (make \"a\" nil)) 2)
+        )
+    )",
+    );
+
+    assert_eq!(
+        eval.eval_exp(&exp.unwrap(), &mut env),
+        Ok(Expression::List(vec![
+            Expression::String(r#"{"at":{"x":1,"y":2},"price":2.5,"tags":[]}"#.to_string()),
+            Expression::String("[\n  1,\n  {\n    \"a\": null\n  }\n]".to_string()),
+        ]))
+    );
+}
+
+#[test]
+fn malformed_json_reports_position() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    env.borrow_mut()
+        .define(
+            "text",
+            Expression::String("{\n  \"a\": [1, 2,]\n}".to_string()),
+        )
+        .unwrap();
+
+    assert_eq!(
+        eval.eval_exp(&parse("(json-parse text)").unwrap(), &mut env),
+        Err(Error::Parse(
+            "invalid json at line 2, column 14: unexpected ']'".to_string()
+        ))
+    );
+    assert_eq!(
+        eval.eval_exp(&parse("(json-parse \"[1, 1e400]\")").unwrap(), &mut env),
+        Err(Error::Parse(
+            "invalid json at line 1, column 10: number 1e400 is out of range".to_string()
+        ))
+    );
+}
@@ -1,4 +1,4 @@
-use ema::lexer::{tokenize, Token};
+use ema::lexer::{tokenize, StringPart, Token};
 
 use Token::*;
 #[test]
@@ -81,3 +81,32 @@ fn tokenize_correctly() {
         ])
     )
 }
+
+#[test]
+fn tokenize_interpolated_string() {
+    let res = tokenize("(print f\"x = {x:.2}, {(+ x 1)} {{}}\")");
+    assert_eq!(
+        res,
+        Ok(vec![
+            LParen,
+            Symbol("print".to_string()),
+            Interpolated(vec![
+                StringPart::Text("x = ".to_string()),
+                StringPart::Expr(vec![Symbol("x".to_string())], ".2".to_string()),
+                StringPart::Text(", ".to_string()),
+                StringPart::Expr(
+                    vec![
+                        LParen,
+                        Symbol("+".to_string()),
+                        Symbol("x".to_string()),
+                        Number(1.0),
+                        RParen
+                    ],
+                    "".to_string()
+                ),
+                StringPart::Text(" {}".to_string()),
+            ]),
+            RParen
+        ])
+    )
+}
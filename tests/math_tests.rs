@@ -0,0 +1,121 @@
+use std::{cell::RefCell, rc::Rc};
+
+use ema::{
+    environment::Environment, error::Error, eval::Evaluator, expression::Expression, parser::parse,
+    transform::transform_program_to_block,
+};
+
+#[test]
+fn math_functions_and_constants() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let exp = parse(
+        "
+    (begin
+        (import * std.math)
+        (list
+            (sqrt 16)
+            (floor -2.5)
+            (round 2.5)
+            (round 1.23456 2)
+            (log 8 2)
+            (cos 0)
+            (abs -3)
+            (min 4 2 8)
+            (max (list 4 2 8))
+        )
+    )",
+    );
+
+    assert_eq!(
+        eval.eval_exp(&exp.unwrap(), &mut env),
+        Ok(Expression::List(vec![
+            Expression::Number(4.0),
+            Expression::Number(-3.0),
+            Expression::Number(3.0),
+            Expression::Number(1.23),
+            Expression::Number(3.0),
+            Expression::Number(1.0),
+            Expression::Number(3.0),
+            Expression::Number(2.0),
+            Expression::Number(8.0),
+        ]))
+    );
+}
+
+#[test]
+fn integer_helpers_and_predicates() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let exp = parse(
+        "
+    (begin
+        (import * std.math)
+        (list
+            (gcd 12 18)
+            (lcm 4 6)
+            (clamp 15 0 10)
+            (nan? nan)
+            (infinite? (/ 1 0))
+            (finite? infinity)
+            (integer? 2.5)
+        )
+    )",
+    );
+
+    assert_eq!(
+        eval.eval_exp(&exp.unwrap(), &mut env),
+        Ok(Expression::List(vec![
+            Expression::Number(6.0),
+            Expression::Number(12.0),
+            Expression::Number(10.0),
+            Expression::Boolean(true),
+            Expression::Boolean(true),
+            Expression::Boolean(false),
+            Expression::Boolean(false),
+        ]))
+    );
+}
+
+#[test]
+fn math_domain_errors() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    eval.eval_exp(&program("(import * std.math)"), &mut env)
+        .unwrap();
+
+    assert_eq!(
+        eval.eval_exp(&program("(sqrt -1)"), &mut env),
+        Err(Error::Reason(
+            "math domain error: sqrt of -1, expected a non-negative number".to_string()
+        ))
+    );
+    assert_eq!(
+        eval.eval_exp(&program("(gcd 1.5 3)"), &mut env),
+        Err(Error::Invalid(
+            "gcd expects an integer, got 1.5".to_string()
+        ))
+    );
+    assert_eq!(
+        eval.eval_exp(&program("(gcd 1e30 10)"), &mut env),
+        Err(Error::Invalid(
+            "gcd expects an integer, got 1000000000000000000000000000000".to_string()
+        ))
+    );
+    assert_eq!(
+        eval.eval_exp(&program("(gcd -9007199254740991 0)"), &mut env),
+        Ok(Expression::Number(9007199254740991.0))
+    );
+    assert_eq!(
+        eval.eval_exp(&program("(lcm 9007199254740991 9007199254740990)"), &mut env),
+        Err(Error::Reason(
+            "math domain error: lcm of 9007199254740991 and 9007199254740990 is above max-safe-integer"
+                .to_string()
+        ))
+    );
+}
+
+// a source holding one form evaluates to that form, like a program file
+fn program(source: &str) -> Expression {
+    transform_program_to_block(&parse(source).unwrap()).unwrap()
+}
@@ -0,0 +1,95 @@
+use std::{cell::RefCell, rc::Rc};
+
+use ema::{environment::Environment, eval::Evaluator, expression::Expression, parser::parse};
+
+#[test]
+fn apply_and_funcall() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let exp = parse(
+        "
+    (begin
+        (def sum (x y z) (+ (+ x y) z))
+        (var args (list 1 (+ 1 1) 3))
+        (+ (apply sum args) (funcall sum 10 20 30))
+    )",
+    );
+
+    assert_eq!(
+        eval.eval_exp(&exp.unwrap(), &mut env),
+        Ok(Expression::Number(66.0))
+    );
+
+    // the built-ins are functions, definitions with the same name shadow them
+    let program = parse(
+        "
+    (def list (a b) (+ a b))
+    (def fields (o) 99)
+    (var parse (lambda (text) 1000))
+    (+ (+ (list 1 2) (fields 1)) (apply parse (quote (\"(+ 1 2)\"))))
+    ",
+    );
+
+    assert_eq!(
+        eval.eval_program(&program.unwrap(), &mut env),
+        Ok(Expression::Number(1102.0))
+    );
+}
+
+#[test]
+fn eval_quoted_expression() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let program = parse(
+        "
+    (var x 10)
+    (module Math
+        (begin
+            (var x 100)
+        )
+    )
+    (var code (quote (* x 2)))
+    (def f ()
+        (begin
+            (var x 1)
+            (var current Math)
+            (list (eval code) (eval code \"current\") (eval code \"global\") (eval code Math) (eval code current))
+        )
+    )
+    (f)
+    ",
+    );
+
+    assert_eq!(
+        eval.eval_program(&program.unwrap(), &mut env),
+        Ok(Expression::List(vec![
+            Expression::Number(2.0),
+            Expression::Number(2.0),
+            Expression::Number(20.0),
+            Expression::Number(200.0),
+            Expression::Number(200.0),
+        ]))
+    );
+}
+
+#[test]
+fn parse_and_eval() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let exp = parse(
+        "
+    (begin
+        (var code (parse \"(+ 2 (* 3 4))\"))
+        (var forms (parse \"(var a (eval code)) (var b (+ a 1)) (list a b)\"))
+        (eval forms)
+    )",
+    );
+
+    assert_eq!(
+        eval.eval_exp(&exp.unwrap(), &mut env),
+        Ok(Expression::List(vec![
+            Expression::Number(14.0),
+            Expression::Number(15.0),
+        ]))
+    );
+}
@@ -0,0 +1,125 @@
+use std::{cell::RefCell, rc::Rc};
+
+use ema::{environment::Environment, eval::Evaluator, expression::Expression, parser::parse};
+
+const VECTOR: &str = "
+    (class Vector nil
+        (begin
+            (def constructor (self x y)
+                (begin
+                    (set (prop self x) x)
+                    (set (prop self y) y)
+                )
+            )
+
+            (def __add__ (self other)
+                (new Vector (+ (prop self x) (prop other x)) (+ (prop self y) (prop other y)))
+            )
+
+            (def __mul__ (self k)
+                (new Vector (* (prop self x) k) (* (prop self y) k))
+            )
+
+            (def __rmul__ (self k)
+                (. self __mul__ k)
+            )
+
+            (def __eq__ (self other)
+                (& (= (prop self x) (prop other x)) (= (prop self y) (prop other y)))
+            )
+
+            (def __lt__ (self other)
+                (< (+ (prop self x) (prop self y)) (+ (prop other x) (prop other y)))
+            )
+        )
+    )
+";
+
+#[test]
+fn arithmetic_and_comparison_operators() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let exp = parse(&format!(
+        "
+    (begin
+        {}
+        (var a (new Vector 1 2))
+        (var b (new Vector 3 4))
+        (var c (+ a b))
+        (list
+            (prop c x)
+            (prop (* 2 c) y)
+            (= c (new Vector 4 6))
+            (!= c a)
+            (< a b)
+            (> a b)
+        )
+    )",
+        VECTOR
+    ));
+
+    assert_eq!(
+        eval.eval_exp(&exp.unwrap(), &mut env),
+        Ok(Expression::List(vec![
+            Expression::Number(4.0),
+            Expression::Number(12.0),
+            Expression::Boolean(true),
+            Expression::Boolean(true),
+            Expression::Boolean(true),
+            Expression::Boolean(false),
+        ]))
+    );
+}
+
+#[test]
+fn identity_equality_without_eq() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let exp = parse(
+        "
+    (begin
+        (class Money nil
+            (def constructor (self amount) (set (prop self amount) amount))
+        )
+        (var a (new Money 1))
+        (var b (new Money 1))
+        (list (= a a) (= a b) (!= a b))
+    )",
+    );
+
+    assert_eq!(
+        eval.eval_exp(&exp.unwrap(), &mut env),
+        Ok(Expression::List(vec![
+            Expression::Boolean(true),
+            Expression::Boolean(false),
+            Expression::Boolean(true),
+        ]))
+    );
+}
+
+#[test]
+fn call_get_and_str_methods() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let exp = parse(
+        "
+    (begin
+        (class Adder nil
+            (begin
+                (def constructor (self n) (set (prop self n) n))
+                (def __call__ (self x) (+ x (prop self n)))
+                (def __get__ (self name) (+ \"missing \" name))
+                (def __str__ (self) \"<adder>\")
+            )
+        )
+        (var add10 (new Adder 10))
+        (list (add10 5) (apply add10 (list 1)) (prop add10 foo) add10)
+    )",
+    );
+
+    let res = eval.eval_exp(&exp.unwrap(), &mut env).unwrap();
+    assert_eq!(
+        eval.stringify(&res),
+        Ok("(15 11 missing foo <adder>)".to_string())
+    );
+}
@@ -0,0 +1,64 @@
+use std::{cell::RefCell, rc::Rc};
+
+use ema::{
+    environment::Environment, error::Error, eval::Evaluator, expression::Expression, parser::parse,
+    run_code,
+};
+
+#[test]
+fn argv_and_environment_variables() {
+    let mut eval = Evaluator::default();
+    eval.set_argv(vec!["--verbose".to_string(), "input.txt".to_string()]);
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let exp = parse(
+        "
+    (begin
+        (setenv \"EMA_PROCESS_TEST\" \"on\")
+        (list
+            argv
+            (getenv \"EMA_PROCESS_TEST\")
+            (getenv \"EMA_PROCESS_TEST_MISSING\")
+            (getenv \"EMA_PROCESS_TEST_MISSING\" \"off\")
+        )
+    )",
+    );
+
+    assert_eq!(
+        eval.eval_exp(&exp.unwrap(), &mut env),
+        Ok(Expression::List(vec![
+            Expression::List(vec![
+                Expression::String("--verbose".to_string()),
+                Expression::String("input.txt".to_string()),
+            ]),
+            Expression::String("on".to_string()),
+            Expression::Void,
+            Expression::String("off".to_string()),
+        ]))
+    );
+}
+
+#[test]
+fn exit_is_not_caught_by_try() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let exp = parse("(try (exit 4) (catch err 0))");
+
+    assert_eq!(eval.eval_exp(&exp.unwrap(), &mut env), Err(Error::Exit(4)));
+}
+
+#[test]
+fn run_code_exit_status() {
+    let args = vec!["a".to_string(), "b".to_string()];
+    assert_eq!(
+        run_code("tests/scripts/exit_code.ema".to_string(), vec![], args),
+        2
+    );
+    assert_eq!(
+        run_code("tests/scripts/failing.ema".to_string(), vec![], vec![]),
+        1
+    );
+    assert_eq!(
+        run_code("tests/scripts/missing.ema".to_string(), vec![], vec![]),
+        1
+    );
+}
@@ -0,0 +1,82 @@
+use std::{cell::RefCell, rc::Rc};
+
+use ema::{
+    environment::Environment, error::Error, eval::Evaluator, expression::Expression, parser::parse,
+};
+
+const ROLLS: &str = "
+    (begin
+        (import * std.random)
+        (list (random-int 1 6) (random-int 1 6) (random-int 1 6) (choice (list \"a\" \"b\" \"c\")))
+    )";
+
+fn roll(eval: &mut Evaluator) -> Result<Expression, Error> {
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    eval.eval_exp(&parse(ROLLS).unwrap(), &mut env)
+}
+
+#[test]
+fn seeded_evaluators_repeat_the_same_numbers() {
+    let mut first = Evaluator::default();
+    first.set_seed(42);
+    let mut second = Evaluator::default();
+    second.set_seed(42);
+
+    let expected = Ok(Expression::List(vec![
+        Expression::Number(2.0),
+        Expression::Number(2.0),
+        Expression::Number(1.0),
+        Expression::String("a".to_string()),
+    ]));
+    assert_eq!(roll(&mut first), expected);
+    assert_eq!(roll(&mut second), expected);
+}
+
+#[test]
+fn seed_from_ema_resets_the_sequence() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let exp = parse(
+        "
+    (begin
+        (import * std.random)
+        (seed 7)
+        (var a (list (random) (shuffle (list 1 2 3 4 5))))
+        (seed 7)
+        (var b (list (random) (shuffle (list 1 2 3 4 5))))
+        (import sort std.lists)
+        (list (= a b) (sort (shuffle (list 3 1 2))))
+    )",
+    );
+
+    assert_eq!(
+        eval.eval_exp(&exp.unwrap(), &mut env),
+        Ok(Expression::List(vec![
+            Expression::Boolean(true),
+            Expression::List(vec![
+                Expression::Number(1.0),
+                Expression::Number(2.0),
+                Expression::Number(3.0),
+            ]),
+        ]))
+    );
+}
+
+#[test]
+fn random_errors() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    eval.eval_exp(&parse("(import * std.random)").unwrap(), &mut env)
+        .unwrap();
+
+    assert_eq!(
+        eval.eval_exp(&parse("(choice (list))").unwrap(), &mut env),
+        Err(Error::Reason(
+            "cannot choose from an empty list".to_string()
+        ))
+    );
+    assert_eq!(
+        eval.eval_exp(&parse("(random-int 5 1)").unwrap(), &mut env),
+        Err(Error::Reason("random-int range 5..1 is empty".to_string()))
+    );
+}
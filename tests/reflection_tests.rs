@@ -0,0 +1,124 @@
+use std::{cell::RefCell, rc::Rc};
+
+use ema::{
+    environment::Environment, error::Error, eval::Evaluator, expression::Expression, parser::parse,
+};
+
+const POINTS: &str = "
+    (class Point nil
+        (begin
+            (def constructor (self x y)
+                (begin
+                    (set (prop self x) x)
+                    (set (prop self y) y)
+                )
+            )
+
+            (def calc (self)
+                (+ (prop self x) (prop self y))
+            )
+        )
+    )
+
+    (class Point3D Point
+        (begin
+            (def constructor (self x y z)
+                (begin
+                    (. (super Point3D) constructor x y)
+                    (set (prop self z) z)
+                )
+            )
+
+            (def length (self) 3)
+        )
+    )
+
+    (var p (new Point3D 10 30 50))
+";
+
+fn eval_with_points(code: &str) -> Result<Expression, Error> {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    eval.eval_exp(&parse(&format!("(begin {} {})", POINTS, code))?, &mut env)
+}
+
+#[test]
+fn class_predicates() {
+    assert_eq!(
+        eval_with_points(
+            "(list (instance-of? p Point3D) (instance-of? p Point) (instance-of? Point p) (instance-of? 1 Point))"
+        ),
+        Ok(Expression::List(vec![
+            Expression::Boolean(true),
+            Expression::Boolean(true),
+            Expression::Boolean(false),
+            Expression::Boolean(false),
+        ]))
+    );
+
+    assert_eq!(
+        eval_with_points(
+            "(list (class-name p) (class-name (class-of p)) (class-name (parent-class Point3D)) (parent-class Point))"
+        ),
+        Ok(Expression::List(vec![
+            Expression::String("Point3D".to_string()),
+            Expression::String("Point3D".to_string()),
+            Expression::String("Point".to_string()),
+            Expression::Void,
+        ]))
+    );
+}
+
+#[test]
+fn enumerate_fields_and_methods() {
+    let names = |names: &[&str]| {
+        Expression::List(
+            names
+                .iter()
+                .map(|name| Expression::String(name.to_string()))
+                .collect(),
+        )
+    };
+
+    assert_eq!(
+        eval_with_points("(list (fields p) (methods p))"),
+        Ok(Expression::List(vec![
+            names(&["x", "y", "z"]),
+            names(&["calc", "constructor", "length"]),
+        ]))
+    );
+
+    assert_eq!(
+        eval_with_points("(list (has-prop? p \"calc\") (has-prop? p \"z\") (has-prop? p \"w\"))"),
+        Ok(Expression::List(vec![
+            Expression::Boolean(true),
+            Expression::Boolean(true),
+            Expression::Boolean(false),
+        ]))
+    );
+}
+
+#[test]
+fn display_objects() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let exp = parse(&format!("(begin {} (list p Point3D))", POINTS)).unwrap();
+    let res = eval.eval_exp(&exp, &mut env).unwrap();
+
+    assert_eq!(
+        eval.stringify(&res).unwrap(),
+        "(<Point3D instance {x: 10, y: 30, z: 50}> <class Point3D>)"
+    );
+
+    // Display shows the fields as well, e.g. in error messages
+    assert_eq!(
+        res.to_string(),
+        "(<Point3D instance {x: 10, y: 30, z: 50}> <class Point3D>)"
+    );
+    assert_eq!(
+        eval_with_points("(json-parse p)"),
+        Err(Error::Invalid(
+            "json-parse expects a string, got <Point3D instance {x: 10, y: 30, z: 50}>".to_string()
+        ))
+    );
+}
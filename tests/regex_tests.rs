@@ -0,0 +1,79 @@
+use std::{cell::RefCell, rc::Rc};
+
+use ema::{environment::Environment, eval::Evaluator, parser::parse};
+
+#[test]
+fn match_and_find() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let exp = parse(
+        r#"
+    (begin
+        (import (compile matches? find find-all) std.regex)
+        (var digits (compile "[0-9]+"))
+        (list
+            (matches? digits "abc 42")
+            (matches? "^[a-z]+$" "abc 42")
+            (find digits "abc 42 and 7")
+            (find digits "none")
+            (find-all digits "abc 42 and 7")
+            digits)
+    )"#,
+    );
+
+    let res = eval.eval_exp(&exp.unwrap(), &mut env).unwrap();
+    assert_eq!(
+        eval.stringify(&res),
+        Ok("(true false 42 nil (42 7) <regex [0-9]+>)".to_string())
+    );
+}
+
+#[test]
+fn captures_by_index_and_name() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let exp = parse(
+        r#"
+    (begin
+        (import * std.regex)
+        (var date "(?P<year>[0-9]{4})-(?P<month>[0-9]{2})(-(?P<day>[0-9]{2}))?")
+        (list
+            (captures date "released 2023-08")
+            (named-captures date "released 2023-08-14")
+            (captures date "unreleased"))
+    )"#,
+    );
+
+    let res = eval.eval_exp(&exp.unwrap(), &mut env).unwrap();
+    assert_eq!(
+        eval.stringify(&res),
+        Ok("((2023-08 2023 08 nil nil) {day: 14, month: 08, year: 2023} nil)".to_string())
+    );
+}
+
+#[test]
+fn replace_split_and_errors() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let exp = parse(
+        r#"
+    (begin
+        (import * std.regex)
+        (import nth std.lists)
+        (import to-number std.strings)
+        (import lookup std.maps)
+        (list
+            (replace "([a-z]+)@" "me@host you@host" "$1 at ")
+            (replace-all "[0-9]+" "1 and 22" (lambda (groups) (* 2 (to-number (nth groups 0)))))
+            (split ", *" "a,b,  c")
+            (escape "1+1=2?")
+            (try (compile "(unclosed") (catch err (lookup err "kind"))))
+    )"#,
+    );
+
+    let res = eval.eval_exp(&exp.unwrap(), &mut env).unwrap();
+    assert_eq!(
+        eval.stringify(&res),
+        Ok(r"(me at host you@host 2 and 44 (a b c) 1\+1=2\? invalid)".to_string())
+    );
+}
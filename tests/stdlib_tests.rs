@@ -0,0 +1,103 @@
+use std::{cell::RefCell, rc::Rc};
+
+use ema::{
+    environment::Environment, error::Error, eval::Evaluator, expression::Expression, parser::parse,
+};
+
+#[test]
+fn import_std_modules() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let exp = parse(
+        "
+    (begin
+        (import std.strings)
+        (import (map filter sum range length) std.lists)
+        (import * std.math)
+        (list
+            ((prop strings upper) \"ema\")
+            ((prop strings join) (list 1 2 3) \"-\")
+            (sum (map (filter (range 10) (lambda (x) (even? x))) square))
+            (abs -4)
+            (length (range 1e16 (+ 1e16 2)))
+            (range 0 1 0.25)
+        )
+    )",
+    );
+
+    assert_eq!(
+        eval.eval_exp(&exp.unwrap(), &mut env),
+        Ok(Expression::List(vec![
+            Expression::String("EMA".to_string()),
+            Expression::String("1-2-3".to_string()),
+            Expression::Number(120.0),
+            Expression::Number(4.0),
+            Expression::Number(2.0),
+            Expression::List(vec![
+                Expression::Number(0.0),
+                Expression::Number(0.25),
+                Expression::Number(0.5),
+                Expression::Number(0.75),
+            ]),
+        ]))
+    );
+
+    assert_eq!(
+        eval.eval_exp(
+            &parse("(begin (import range std.lists) (range 0 20000000))").unwrap(),
+            &mut env
+        ),
+        Err(Error::Reason(
+            "range from 0 to 20000000 by 1 has more than 10000000 items".to_string()
+        ))
+    );
+}
+
+#[test]
+fn std_maps_are_values() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let exp = parse(
+        "
+    (begin
+        (import (make put lookup keys) std.maps)
+        (var m (make \"b\" 2 \"a\" 1))
+        (var n (put m \"c\" 3))
+        (list (keys m) (lookup n \"c\") (lookup m \"c\" 0) (= m (make \"a\" 1 \"b\" 2)))
+    )",
+    );
+
+    let res = eval.eval_exp(&exp.unwrap(), &mut env).unwrap();
+    assert_eq!(eval.stringify(&res), Ok("((a b) 3 0 true)".to_string()));
+}
+
+#[test]
+fn std_modules_are_shadowed_only_explicitly() {
+    let mut eval = Evaluator::default();
+    eval.set_cwf_path("tests/modules".to_string());
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let exp = parse(
+        "
+    (begin
+        (import abs std.math)
+        (import (abs as local-abs) ./std/math)
+        (list (abs -1) (local-abs -1))
+    )",
+    );
+
+    assert_eq!(
+        eval.eval_exp(&exp.unwrap(), &mut env),
+        Ok(Expression::List(vec![
+            Expression::Number(1.0),
+            Expression::String("local abs".to_string()),
+        ]))
+    );
+
+    let exp = parse("(import std.missing)");
+    assert_eq!(
+        eval.eval_exp(&exp.unwrap(), &mut env),
+        Err(Error::Reference(
+            "cannot find module std.missing in the standard library".to_string()
+        ))
+    );
+}
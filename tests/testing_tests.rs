@@ -0,0 +1,116 @@
+use std::{cell::RefCell, path::Path, rc::Rc};
+
+use ema::{
+    environment::Environment,
+    error::Error,
+    eval::Evaluator,
+    expression::Expression,
+    parser::parse,
+    testing::{discover, run_file},
+};
+
+#[test]
+fn deftest_declares_tests_without_running_them() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let exp = parse(
+        r#"
+    (begin
+        (var total 1)
+        (deftest "passes" (assert-eq (+ total 1) 2))
+        (deftest fails
+            (var total 5)
+            (assert-eq (list total "x") (list 5 "y")))
+        total
+    )"#,
+    );
+
+    assert_eq!(
+        eval.eval_exp(&exp.unwrap(), &mut env),
+        Ok(Expression::Number(1.0))
+    );
+
+    let tests = eval.take_tests();
+    let results = tests
+        .iter()
+        .map(|test| (test.name.clone(), eval.run_test(test)))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        results,
+        vec![
+            ("passes".to_string(), Ok(())),
+            (
+                "fails".to_string(),
+                Err(Error::Assertion(
+                    "assert-eq failed\n  expected: (5 \"y\")\n  actual:   (5 \"x\")".to_string()
+                ))
+            ),
+        ]
+    );
+    assert!(eval.take_tests().is_empty());
+}
+
+#[test]
+fn assert_throws() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let exp = parse(
+        r#"
+    (begin
+        (import lookup std.maps)
+        (list
+            (lookup (assert-throws (throw "boom")) "message")
+            (lookup (assert-throws undefined-name "reference") "kind")
+            (try (assert-throws (+ 1 2)) (catch err (lookup err "message")))
+            (try (assert-throws (throw "boom") "io") (catch err (lookup err "kind"))))
+    )"#,
+    );
+
+    assert_eq!(
+        eval.eval_exp(&exp.unwrap(), &mut env),
+        Ok(Expression::List(vec![
+            Expression::String("boom".to_string()),
+            Expression::String("reference".to_string()),
+            Expression::String(
+                "assert-throws failed\n  expected: an error\n  actual:   returned 3".to_string()
+            ),
+            Expression::String("assertion".to_string()),
+        ]))
+    );
+}
+
+#[test]
+fn discover_and_run_test_files() {
+    let files = discover(&["tests/scripts/testing".to_string()]).unwrap();
+    assert_eq!(
+        files,
+        vec![
+            Path::new("tests/scripts/testing/nested/report_test.ema"),
+            Path::new("tests/scripts/testing/shapes_test.ema"),
+        ]
+    );
+
+    let results = run_file(&files[1], &[])
+        .into_iter()
+        .map(|result| (result.name, result.error.map(|err| err.kind())))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        results,
+        vec![
+            ("computes areas".to_string(), None),
+            ("scopes are isolated".to_string(), None),
+            ("sees the file scope".to_string(), None),
+            ("changes the file scope".to_string(), None),
+            ("does not see other tests changes".to_string(), None),
+            ("wrong area".to_string(), Some("assertion")),
+            ("throws".to_string(), None),
+            ("does not throw".to_string(), Some("assertion")),
+        ]
+    );
+
+    let report = run_file(&files[0], &[]).remove(0).error.unwrap();
+    assert_eq!(
+        report.message(),
+        "assert-eq failed, strings differ (- expected, + actual)\n    total\n    apples 3\n  - pears 5\n  + pears 4"
+    );
+}
@@ -0,0 +1,93 @@
+use std::{cell::RefCell, rc::Rc};
+
+use ema::{environment::Environment, eval::Evaluator, expression::Expression, parser::parse};
+
+#[test]
+fn parse_and_format_datetimes() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let exp = parse(
+        r#"
+    (begin
+        (import * std.time)
+        (var release (parse-datetime "2024-02-28T22:30:00+02:00"))
+        (list
+            release
+            (with-offset release "Z")
+            (parse-datetime "2024-02-28")
+            (parse-datetime "28/02/2024 09:05" "%d/%m/%Y %H:%M")
+            (format-datetime release "%A %d %B %Y, %H:%M")
+            (format-datetime (datetime 2024 1 2 3 4 5 678))
+            (timestamp (from-timestamp 1700000000000)))
+    )"#,
+    );
+
+    let res = eval.eval_exp(&exp.unwrap(), &mut env).unwrap();
+    assert_eq!(
+        eval.stringify(&res),
+        Ok("(2024-02-28T22:30:00+02:00 2024-02-28T20:30:00Z 2024-02-28T00:00:00Z 2024-02-28T09:05:00Z Wednesday 28 February 2024, 22:30 2024-01-02T03:04:05.678Z 1700000000000)".to_string())
+    );
+}
+
+#[test]
+fn duration_arithmetic() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let exp = parse(
+        r#"
+    (begin
+        (import * std.time)
+        (import lookup std.maps)
+        (var start (datetime 2024 2 28 12 0 0))
+        (var later (+ start (days 2)))
+        (list
+            later
+            (- later (hours 1))
+            (/ (- later start) (hours 1))
+            (diff start later)
+            (add start (seconds 90))
+            (< start later)
+            (= start (with-offset start 60))
+            (lookup (parts later) "weekday"))
+    )"#,
+    );
+
+    let res = eval.eval_exp(&exp.unwrap(), &mut env).unwrap();
+    assert_eq!(
+        eval.stringify(&res),
+        Ok("(2024-03-01T12:00:00Z 2024-03-01T11:00:00Z 48 -172800000 2024-02-28T12:01:30Z true true 5)".to_string())
+    );
+}
+
+#[test]
+fn clocks_and_errors() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let exp = parse(
+        r#"
+    (begin
+        (import * std.time)
+        (import lookup std.maps)
+        (import std.time as time)
+        (var start (monotonic))
+        (sleep 5)
+        (list
+            (>= (- (monotonic) start) 5)
+            (>= ((prop time monotonic)) start)
+            (> (timestamp (datetime)) 1700000000000)
+            (try (parse-datetime "yesterday") (catch err (lookup err "kind")))
+            (try (datetime 2023 2 29) (catch err (lookup err "message"))))
+    )"#,
+    );
+
+    assert_eq!(
+        eval.eval_exp(&exp.unwrap(), &mut env),
+        Ok(Expression::List(vec![
+            Expression::Boolean(true),
+            Expression::Boolean(true),
+            Expression::Boolean(true),
+            Expression::String("invalid".to_string()),
+            Expression::String("invalid datetime 2023-02-29 00:00:00.000".to_string()),
+        ]))
+    );
+}
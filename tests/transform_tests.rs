@@ -1,6 +1,9 @@
 use std::{cell::RefCell, rc::Rc};
 
-use ema::{environment::Environment, eval::Evaluator, expression::Expression, parser::parse};
+use ema::{
+    environment::Environment, eval::Evaluator, expression::Expression, parser::parse,
+    transform::transform_program_to_block,
+};
 
 // tests for syntactic suger: switch for += ++ -= --
 
@@ -107,3 +110,40 @@ fn shorthand_assign() {
         Ok(Expression::Number(15.0))
     );
 }
+
+#[test]
+fn program_to_block_test() {
+    let mut eval = Evaluator::default();
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let program = parse(
+        "
+    (var x 10)
+    (def double (n) (* n 2))
+    (double x)
+    ",
+    )
+    .unwrap();
+
+    assert_eq!(
+        eval.eval_exp(&transform_program_to_block(&program).unwrap(), &mut env),
+        Ok(Expression::Number(20.0))
+    );
+
+    // a program evaluates its forms in the given environment, so its definitions stay there
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    assert_eq!(
+        eval.eval_program(&program, &mut env),
+        Ok(Expression::Number(20.0))
+    );
+    assert_eq!(env.borrow_mut().lookup("x"), Ok(Expression::Number(10.0)));
+
+    // a single form is kept as is
+    let program = parse("(begin (var y 2) y)").unwrap();
+    assert_eq!(
+        transform_program_to_block(&program),
+        parse("(begin (var y 2) y)").map(|forms| match forms {
+            Expression::List(mut forms) => forms.remove(0),
+            _ => unreachable!(),
+        })
+    );
+}